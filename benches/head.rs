@@ -23,6 +23,24 @@ async fn test_re(
     assert_eq!(resp.status(), 200)
 }
 
+async fn test_re_stale(
+    app: impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = ServiceResponse,
+        Error = actix_web::Error,
+    >,
+) {
+    // A cached copy that is out of date, so the ETag is compared and the
+    // headers are sent again.
+    let req = test::TestRequest::default()
+        .method(Method::HEAD)
+        .uri("/re/")
+        .append_header(("If-None-Match", "\"stale\""))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200)
+}
+
 async fn test_refw(
     app: impl actix_web::dev::Service<
         actix_http::Request,
@@ -39,7 +57,7 @@ async fn test_refw(
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    let mut group = c.benchmark_group("image file");
+    let mut group = c.benchmark_group("head request");
     group.measurement_time(Duration::from_secs(SECS_PER_BENCH));
 
     let runtime = runtime::Builder::new_current_thread().build().unwrap();
@@ -49,6 +67,10 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.to_async(&runtime).iter(|| test_re(app))
     });
 
+    group.bench_with_input("rust_embed, stale etag", &app, |b, app| {
+        b.to_async(&runtime).iter(|| test_re_stale(app))
+    });
+
     group.bench_with_input("rust_embed_for_web", &app, |b, app| {
         b.to_async(&runtime).iter(|| test_refw(app))
     });
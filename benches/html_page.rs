@@ -19,6 +19,23 @@ async fn test_re(
     assert!(resp.starts_with("<!DOCTYPE html>".as_bytes()))
 }
 
+async fn test_re_stale(
+    app: impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = ServiceResponse,
+        Error = actix_web::Error,
+    >,
+) {
+    // A cached copy that is out of date, so the ETag is compared and the
+    // page is sent again.
+    let req = test::TestRequest::get()
+        .uri("/re/")
+        .append_header(("If-None-Match", "\"stale\""))
+        .to_request();
+    let resp = test::call_and_read_body(&app, req).await;
+    assert!(resp.starts_with("<!DOCTYPE html>".as_bytes()))
+}
+
 async fn test_refw(
     app: impl actix_web::dev::Service<
         actix_http::Request,
@@ -42,6 +59,10 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.to_async(&runtime).iter(|| test_re(app))
     });
 
+    group.bench_with_input("rust_embed, stale etag", &app, |b, app| {
+        b.to_async(&runtime).iter(|| test_re_stale(app))
+    });
+
     group.bench_with_input("rust_embed_for_web", &app, |b, app| {
         b.to_async(&runtime).iter(|| test_refw(app))
     });
@@ -0,0 +1,391 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    io::{self, Cursor, Read},
+    path::{Component, Path},
+    sync::{Arc, OnceLock},
+};
+
+use actix_web::{http::header::HeaderValue, web::Bytes};
+use chrono::NaiveDate;
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use zip::{CompressionMethod, ZipArchive};
+
+use crate::{
+    embed::EmbedRespondable,
+    headers::{FileHeaders, HeaderStr},
+    source::EmbedSource,
+};
+
+/// The archive could not be read.
+#[derive(Debug)]
+pub struct ArchiveError {
+    pub message: String,
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid archive: {}", self.message)
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<io::Error> for ArchiveError {
+    fn from(error: io::Error) -> Self {
+        ArchiveError {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<zip::result::ZipError> for ArchiveError {
+    fn from(error: zip::result::ZipError) -> Self {
+        ArchiveError {
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Serves the files in a zip or tar archive, like one embedded with
+/// `include_bytes!`.
+///
+/// Embedding one archive is a lot faster to compile than deriving
+/// `RustEmbed` for a large folder. The archive is read once when the source
+/// is created, and the files get the same ETags as `rust-embed` files with
+/// the same contents.
+///
+/// Zip members compressed with deflate are kept as they are in the archive,
+/// and sent as the gzip compressed response without compressing them again.
+/// Only the compressed data of these members is kept in memory, it is
+/// decompressed the first time a response needs the file uncompressed.
+/// Members that deflate made larger are kept uncompressed instead.
+///
+/// ```ignore
+/// let source = ArchiveSource::zip(&include_bytes!("../assets.zip")[..])?;
+/// App::new().service(EmbedService::new("/", source))
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ArchiveSource {
+    files: Arc<HashMap<String, ArchiveFile>>,
+}
+
+impl ArchiveSource {
+    /// Read a zip archive. Members can be stored or compressed with deflate.
+    /// Archives with encrypted members can't be read.
+    pub fn zip(archive: impl Into<Bytes>) -> Result<Self, ArchiveError> {
+        let mut archive = ZipArchive::new(Cursor::new(archive.into()))?;
+        let mut files = HashMap::new();
+        for index in 0..archive.len() {
+            let mut member = archive.by_index(index)?;
+            if !member.is_file() {
+                continue;
+            }
+            let Some(path) = member.enclosed_name().and_then(|path| member_path(&path)) else {
+                continue;
+            };
+            let last_modified_timestamp = member.last_modified().and_then(|date| {
+                NaiveDate::from_ymd_opt(date.year().into(), date.month().into(), date.day().into())?
+                    .and_hms_opt(
+                        date.hour().into(),
+                        date.minute().into(),
+                        date.second().into(),
+                    )
+                    .map(|date| date.and_utc().timestamp())
+            });
+            let (crc32, size) = (member.crc32(), member.size());
+            // The raw stream of an encrypted member is not deflate, and a
+            // member deflate couldn't shrink is smaller sent as it is.
+            let passthrough = member.compression() == CompressionMethod::Deflated
+                && !member.encrypted()
+                && member.compressed_size() < size;
+            let file = if passthrough {
+                // Reading the member to the end also checks its checksum, so
+                // the deflate stream is known to be good.
+                let hash = hash_reader(&mut member)?;
+                drop(member);
+                let mut deflate = Vec::new();
+                archive.by_index_raw(index)?.read_to_end(&mut deflate)?;
+                ArchiveFile::deflated(
+                    path.clone(),
+                    gzip_from_deflate(&deflate, crc32, size),
+                    hash,
+                    size.try_into().unwrap_or(usize::MAX),
+                    last_modified_timestamp,
+                )
+            } else {
+                let mut data = Vec::with_capacity(size.try_into().unwrap_or_default());
+                member.read_to_end(&mut data)?;
+                ArchiveFile::new(path.clone(), data.into(), last_modified_timestamp)
+            };
+            files.insert(path, file);
+        }
+        Ok(ArchiveSource {
+            files: Arc::new(files),
+        })
+    }
+
+    /// Read an uncompressed tar archive.
+    pub fn tar(archive: impl Into<Bytes>) -> Result<Self, ArchiveError> {
+        Self::read_tar(&archive.into()[..])
+    }
+
+    /// Read a tar archive compressed with gzip, a `.tar.gz` file.
+    pub fn tar_gz(archive: impl Into<Bytes>) -> Result<Self, ArchiveError> {
+        Self::read_tar(GzDecoder::new(&archive.into()[..]))
+    }
+
+    fn read_tar(archive: impl Read) -> Result<Self, ArchiveError> {
+        let mut archive = tar::Archive::new(archive);
+        let mut files = HashMap::new();
+        for member in archive.entries()? {
+            let mut member = member?;
+            if !member.header().entry_type().is_file() {
+                continue;
+            }
+            let Some(path) = member_path(&member.path()?) else {
+                continue;
+            };
+            let last_modified_timestamp = member
+                .header()
+                .mtime()
+                .ok()
+                .and_then(|mtime| mtime.try_into().ok());
+            let mut data = Vec::with_capacity(member.size().try_into().unwrap_or_default());
+            member.read_to_end(&mut data)?;
+            let file = ArchiveFile::new(path.clone(), data.into(), last_modified_timestamp);
+            files.insert(path, file);
+        }
+        Ok(ArchiveSource {
+            files: Arc::new(files),
+        })
+    }
+
+    /// The file at this path.
+    pub fn get(&self, path: &str) -> Option<ArchiveFile> {
+        self.files.get(path.trim_start_matches('/')).cloned()
+    }
+}
+
+impl EmbedSource for ArchiveSource {
+    type File = ArchiveFile;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        ArchiveSource::get(self, path)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        let mut paths: Vec<&str> = self.files.keys().map(String::as_str).collect();
+        paths.sort_unstable();
+        Box::new(paths.into_iter().map(Cow::Borrowed))
+    }
+}
+
+/// The path of a member, like `assets/style.css`. Members with a path that
+/// would leave the archive, like `../style.css`, are skipped.
+fn member_path(path: &Path) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// The sha256 hash of everything the reader returns, without keeping it.
+fn hash_reader(mut reader: impl Read) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 8192];
+    loop {
+        match reader.read(&mut buffer)? {
+            0 => return Ok(hasher.finalize().into()),
+            read => hasher.update(&buffer[..read]),
+        }
+    }
+}
+
+/// Wraps a raw deflate stream from a zip archive into a gzip file, which
+/// only needs a header and the checksum and size zip already has.
+fn gzip_from_deflate(deflate: &[u8], crc32: u32, size: u64) -> Bytes {
+    let mut gzip = Vec::with_capacity(deflate.len() + 18);
+    // Magic number, deflate, no flags, no timestamp, no extra flags, unknown OS.
+    gzip.extend_from_slice(&[0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff]);
+    gzip.extend_from_slice(deflate);
+    gzip.extend_from_slice(&crc32.to_le_bytes());
+    // gzip only has room for the size modulo 2^32.
+    gzip.extend_from_slice(&(size as u32).to_le_bytes());
+    gzip.into()
+}
+
+/// A file from an `ArchiveSource`. Cloning it is cheap.
+#[derive(Clone)]
+pub struct ArchiveFile {
+    /// The uncompressed data. Files that are kept as gzip are only
+    /// decompressed when this is needed, and clones share the result.
+    data: Arc<OnceLock<Bytes>>,
+    data_gzip: Option<Bytes>,
+    size: usize,
+    path: Arc<str>,
+    last_modified_timestamp: Option<i64>,
+    headers: Arc<FileHeaders>,
+}
+
+impl fmt::Debug for ArchiveFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchiveFile")
+            .field("path", &self.path)
+            .field("len", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ArchiveFile {
+    fn new(path: String, data: Bytes, last_modified_timestamp: Option<i64>) -> Self {
+        let hash: [u8; 32] = Sha256::digest(&data).into();
+        ArchiveFile {
+            size: data.len(),
+            data: Arc::new(OnceLock::from(data)),
+            data_gzip: None,
+            path: path.into(),
+            last_modified_timestamp,
+            headers: Arc::new(FileHeaders::new(hash, last_modified_timestamp)),
+        }
+    }
+
+    /// A file that is only kept as gzip. `hash` and `size` are of the
+    /// uncompressed data.
+    fn deflated(
+        path: String,
+        data_gzip: Bytes,
+        hash: [u8; 32],
+        size: usize,
+        last_modified_timestamp: Option<i64>,
+    ) -> Self {
+        ArchiveFile {
+            data: Arc::new(OnceLock::new()),
+            data_gzip: Some(data_gzip),
+            size,
+            path: path.into(),
+            last_modified_timestamp,
+            headers: Arc::new(FileHeaders::new(hash, last_modified_timestamp)),
+        }
+    }
+}
+
+impl EmbedRespondable for ArchiveFile {
+    type Data = Bytes;
+    type DataGzip = Bytes;
+    type DataBr = Bytes;
+    type DataZstd = Bytes;
+    type MimeType = &'static str;
+    type ETag = HeaderStr;
+    type LastModified = HeaderStr;
+
+    fn data(&self) -> Self::Data {
+        self.data
+            .get_or_init(|| {
+                let gzip = self.data_gzip.as_deref().unwrap_or_default();
+                let mut data = Vec::with_capacity(self.size);
+                GzDecoder::new(gzip)
+                    .read_to_end(&mut data)
+                    // The checksum was checked when the archive was read.
+                    .expect("archive member is not valid gzip");
+                data.into()
+            })
+            .clone()
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        self.data_gzip.clone()
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        None
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        None
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        self.last_modified_timestamp
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        self.headers.last_modified.clone()
+    }
+
+    fn etag(&self) -> Self::ETag {
+        self.headers.etag.clone()
+    }
+
+    #[cfg(not(feature = "mime-guess"))]
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        None
+    }
+
+    #[cfg(feature = "mime-guess")]
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        mime_guess::from_path(self.path.as_ref()).first_raw()
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(&self.path))
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        Some(self.headers.etag.header_value().clone())
+    }
+
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        self.headers
+            .last_modified
+            .as_ref()
+            .map(|value| value.header_value().clone())
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use std::path::Path;
+
+    use crate::archive::{gzip_from_deflate, member_path};
+
+    #[test]
+    fn member_paths_stay_inside_the_archive() {
+        assert_eq!(
+            member_path(Path::new("./assets/style.css")).as_deref(),
+            Some("assets/style.css")
+        );
+        assert_eq!(member_path(Path::new("../style.css")), None);
+        assert_eq!(member_path(Path::new("/etc/passwd")), None);
+        assert_eq!(member_path(Path::new(".")), None);
+    }
+
+    #[test]
+    fn deflate_streams_become_valid_gzip() {
+        use flate2::{read::GzDecoder, write::DeflateEncoder, Compression};
+        use std::io::{Read, Write};
+
+        let data = b"hello hello hello hello";
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        let deflate = encoder.finish().unwrap();
+        let mut crc = flate2::Crc::new();
+        crc.update(data);
+        let gzip = gzip_from_deflate(&deflate, crc.sum(), data.len() as u64);
+
+        let mut decoded = Vec::new();
+        GzDecoder::new(&gzip[..]).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, data);
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// A cache that holds at most `max_size` worth of values, dropping the oldest
+/// entries first when it is full.
+///
+/// The global caches use this, since files created at runtime can change
+/// while the server runs. Every version of such a file would otherwise be
+/// kept in memory forever.
+#[derive(Debug)]
+pub(crate) struct BoundedCache<K, V> {
+    entries: HashMap<K, (V, usize)>,
+    order: VecDeque<K>,
+    size: usize,
+    max_size: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> BoundedCache<K, V> {
+    pub(crate) fn new(max_size: usize) -> Self {
+        BoundedCache {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            size: 0,
+            max_size,
+        }
+    }
+
+    pub(crate) fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Add a value that takes up `size`, like the length of the data or `1`
+    /// to count the entries. Values larger than the whole cache are not
+    /// cached at all.
+    pub(crate) fn insert(&mut self, key: K, value: V, size: usize) {
+        if size > self.max_size {
+            return;
+        }
+        if let Some((_, old_size)) = self.entries.remove(&key) {
+            self.size -= old_size;
+            self.order.retain(|cached| cached != &key);
+        }
+        while self.size + size > self.max_size {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some((_, oldest_size)) = self.entries.remove(&oldest) {
+                self.size -= oldest_size;
+            }
+        }
+        self.size += size;
+        self.order.push_back(key.clone());
+        self.entries.insert(key, (value, size));
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::bounded::BoundedCache;
+
+    #[test]
+    fn oldest_entries_are_dropped_first() {
+        let mut cache = BoundedCache::new(10);
+        cache.insert("a", 1, 4);
+        cache.insert("b", 2, 4);
+        cache.insert("c", 3, 4);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(&2));
+        assert_eq!(cache.get("c"), Some(&3));
+    }
+
+    #[test]
+    fn replaced_entries_are_not_counted_twice() {
+        let mut cache = BoundedCache::new(10);
+        cache.insert("a", 1, 5);
+        cache.insert("b", 2, 5);
+        cache.insert("b", 3, 5);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("b"), Some(&3));
+    }
+
+    #[test]
+    fn values_larger_than_the_cache_are_skipped() {
+        let mut cache = BoundedCache::new(10);
+        cache.insert("a", 1, 5);
+        cache.insert("b", 2, 11);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("b"), None);
+    }
+}
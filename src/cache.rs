@@ -0,0 +1,84 @@
+use std::borrow::Cow;
+
+use actix_web::http::header::HeaderValue;
+
+/// How long browsers may reuse a response before checking for a new version.
+///
+/// This sets the `Cache-Control` header of successful responses. The `ETag`
+/// and `Last-Modified` headers are always sent, so browsers can always
+/// revalidate cheaply once the cached response expires.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Browsers may cache the file, but have to check that it hasn't changed
+    /// before every use.
+    ///
+    /// This doesn't actually mean "no caching", it means revalidate before
+    /// using. If we don't do this, web browsers don't try to revalidate assets
+    /// like attached scripts and images. Unless you are using fingerprinting or
+    /// versioning on your assets, you probably want this.
+    #[default]
+    Revalidate,
+    /// Browsers may use the cached file for this many seconds without checking
+    /// if it has changed.
+    MaxAge(u32),
+    /// Browsers may use the cached file for as long as they like.
+    ///
+    /// Only use this if the file names change whenever the contents do, for
+    /// example because they contain a hash of the contents.
+    Immutable,
+    /// Browsers should not cache the file at all.
+    NoStore,
+    /// Send this as the `Cache-Control` header.
+    Custom(Cow<'static, str>),
+}
+
+impl CachePolicy {
+    pub(crate) fn header_value(&self) -> HeaderValue {
+        match self {
+            CachePolicy::Revalidate => HeaderValue::from_static("no-cache"),
+            CachePolicy::MaxAge(seconds) => {
+                // Numbers are always valid in headers
+                HeaderValue::from_str(&format!("max-age={seconds}"))
+                    .unwrap_or_else(|_| HeaderValue::from_static("no-cache"))
+            }
+            CachePolicy::Immutable => HeaderValue::from_static("max-age=31536000, immutable"),
+            CachePolicy::NoStore => HeaderValue::from_static("no-store"),
+            CachePolicy::Custom(value) => HeaderValue::from_str(value)
+                // If the value can't be sent, at least make sure the browser
+                // doesn't keep using an outdated file.
+                .unwrap_or_else(|_| HeaderValue::from_static("no-cache")),
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::cache::CachePolicy;
+
+    #[test]
+    fn default_is_revalidate() {
+        assert_eq!(CachePolicy::default().header_value(), "no-cache");
+    }
+
+    #[test]
+    fn header_values() {
+        assert_eq!(CachePolicy::MaxAge(600).header_value(), "max-age=600");
+        assert_eq!(
+            CachePolicy::Immutable.header_value(),
+            "max-age=31536000, immutable"
+        );
+        assert_eq!(CachePolicy::NoStore.header_value(), "no-store");
+        assert_eq!(
+            CachePolicy::Custom("private, max-age=60".into()).header_value(),
+            "private, max-age=60"
+        );
+    }
+
+    #[test]
+    fn invalid_custom_value_revalidates() {
+        assert_eq!(
+            CachePolicy::Custom("bad\nvalue".into()).header_value(),
+            "no-cache"
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use std::{
-    collections::HashMap,
+    error::Error as StdError,
+    fmt, io,
     io::{BufReader, Write},
-    sync::RwLock,
+    sync::{Arc, RwLock},
 };
 
 use brotli::enc::BrotliEncoderParams;
@@ -9,8 +10,10 @@ use flate2::Compression;
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::{bounded::BoundedCache, compression_rules::Encoding, parse::parse_mime_essence};
+
 /// When should the server try sending a compressed response?
-#[derive(Default)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Compress {
     /// Never compress responses, even if a precompressed response is available.
     Never,
@@ -27,7 +30,8 @@ pub enum Compress {
     /// Perform on-the-fly compression if the file mime type is well known to be compressible.
     ///
     /// This option allows you to use compression with `rust-embed-for-web` when the resource is tagged with `#[gzip = "false"]`.
-    /// This will use some CPU to compress the file on the fly before responding. Compressed versions are cached in memory.
+    /// This will use some CPU to compress the file on the fly before responding. Compressed versions are cached in memory,
+    /// up to 32 MiB for each encoding, dropping the oldest ones first.
     ///
     IfWellKnown,
     /// With this option set, the file is always compressed (as long as the client supports it).
@@ -39,119 +43,186 @@ pub enum Compress {
     Always,
 }
 
+/// Compressing a response failed. The response is sent uncompressed instead.
+#[derive(Debug)]
+pub struct CompressionError {
+    encoding: Encoding,
+    source: io::Error,
+}
+
+impl CompressionError {
+    pub(crate) fn new(encoding: Encoding, source: io::Error) -> Self {
+        CompressionError { encoding, source }
+    }
+
+    /// The encoding the response was being compressed with.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+}
+
+impl fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to compress with {}: {}",
+            self.encoding.name(),
+            self.source
+        )
+    }
+}
+
+impl StdError for CompressionError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// A function that is told about compression failures, like one that logs
+/// them.
+#[derive(Clone)]
+pub(crate) struct CompressionErrorHook(Arc<dyn Fn(&CompressionError) + Send + Sync>);
+
+impl CompressionErrorHook {
+    pub(crate) fn new(hook: impl Fn(&CompressionError) + Send + Sync + 'static) -> Self {
+        CompressionErrorHook(Arc::new(hook))
+    }
+
+    pub(crate) fn report(&self, error: &CompressionError) {
+        (self.0)(error)
+    }
+}
+
+impl fmt::Debug for CompressionErrorHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CompressionErrorHook")
+    }
+}
+
 /// This is basically a list of text mime types, plus javascript, json, and xml.
+///
+/// Any parameters like `; charset=utf-8` are ignored.
 pub(crate) fn is_well_known_compressible_mime_type(mime_type: &str) -> bool {
     lazy_static! {
-        static ref RE: Regex =
-            Regex::new(r#"^text/.*|application/(javascript|json5?|(ld|jsonml)[+]json|xml)$"#)
-                .unwrap();
+        static ref RE: Regex = Regex::new(
+            r#"(?i)^(text/.*|application/(javascript|json5?|(ld|jsonml|manifest)[+]json|xml)|image/svg[+]xml)$"#
+        )
+        .unwrap();
     }
-    RE.is_match(mime_type)
+    RE.is_match(parse_mime_essence(mime_type))
 }
 
+/// How much compressed data is cached for each encoding.
+const MAX_CACHED_BYTES: usize = 32 * 1024 * 1024;
+
 // Putting the data into cache could potentially fail. That's okay if it does
 // happen, we have no way of handling that and we might as well just keep
 // serving files.
-#[allow(unused_must_use)]
-/// Compresses data with gzip encoding.
-///
-/// The compressed files are cached based on the hash values provided.
-/// Since we already have the hashes precomputed in rust-embed and rust-embed-for-web,
-/// we just reuse that instead of trying to hash the data this function gets.
-pub(crate) fn compress_data_gzip(hash: &str, data: &[u8]) -> Vec<u8> {
-    lazy_static! {
-        static ref CACHED_GZIP_DATA: RwLock<HashMap<String, Vec<u8>>> = RwLock::new(HashMap::new());
-    }
-
-    if let Some(data_gzip) = CACHED_GZIP_DATA
+/// Looks up the compressed data in the cache, or compresses it with `encode`
+/// and caches it. Failures are not cached, so the data is compressed again
+/// next time.
+pub(crate) fn compress_cached(
+    cache: &RwLock<BoundedCache<String, Vec<u8>>>,
+    hash: &str,
+    data: &[u8],
+    encode: impl FnOnce(&[u8]) -> Result<Vec<u8>, CompressionError>,
+) -> Result<Vec<u8>, CompressionError> {
+    if let Some(compressed) = cache
         .read()
         .ok()
         .and_then(|cached| cached.get(hash).map(ToOwned::to_owned))
     {
-        return data_gzip;
+        return Ok(compressed);
     }
 
-    let mut compressed: Vec<u8> = Vec::new();
-    flate2::write::GzEncoder::new(&mut compressed, Compression::default())
-        .write_all(data)
-        .unwrap();
-    CACHED_GZIP_DATA
-        .write()
-        .map(|mut cached| cached.insert(hash.to_string(), compressed.clone()));
-    compressed
+    let compressed = encode(data)?;
+    if let Ok(mut cached) = cache.write() {
+        cached.insert(hash.to_string(), compressed.clone(), compressed.len());
+    }
+    Ok(compressed)
 }
 
-// Putting the data into cache could potentially fail. That's okay if it does
-// happen, we have no way of handling that and we might as well just keep
-// serving files.
-#[allow(unused_must_use)]
 /// Compresses data with gzip encoding.
 ///
 /// The compressed files are cached based on the hash values provided.
 /// Since we already have the hashes precomputed in rust-embed and rust-embed-for-web,
 /// we just reuse that instead of trying to hash the data this function gets.
-pub(crate) fn compress_data_br(hash: &str, data: &[u8]) -> Vec<u8> {
+pub(crate) fn compress_data_gzip(hash: &str, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
     lazy_static! {
-        static ref CACHED_BR_DATA: RwLock<HashMap<String, Vec<u8>>> = RwLock::new(HashMap::new());
+        static ref CACHED_GZIP_DATA: RwLock<BoundedCache<String, Vec<u8>>> =
+            RwLock::new(BoundedCache::new(MAX_CACHED_BYTES));
     }
 
-    if let Some(data_gzip) = CACHED_BR_DATA
-        .read()
-        .ok()
-        .and_then(|cached| cached.get(hash).map(ToOwned::to_owned))
-    {
-        return data_gzip;
+    compress_cached(&CACHED_GZIP_DATA, hash, data, encode_gzip)
+}
+
+/// Compresses data with brotli encoding.
+///
+/// The compressed files are cached based on the hash values provided.
+/// Since we already have the hashes precomputed in rust-embed and rust-embed-for-web,
+/// we just reuse that instead of trying to hash the data this function gets.
+pub(crate) fn compress_data_br(hash: &str, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    lazy_static! {
+        static ref CACHED_BR_DATA: RwLock<BoundedCache<String, Vec<u8>>> =
+            RwLock::new(BoundedCache::new(MAX_CACHED_BYTES));
     }
 
-    let mut data_read = BufReader::new(data);
-    let mut compressed: Vec<u8> = Vec::new();
-    brotli::BrotliCompress(
-        &mut data_read,
-        &mut compressed,
-        &BrotliEncoderParams::default(),
-    )
-    .expect("Failed to compress br data");
-    CACHED_BR_DATA
-        .write()
-        .map(|mut cached| cached.insert(hash.to_string(), compressed.clone()));
-    compressed
+    compress_cached(&CACHED_BR_DATA, hash, data, encode_br)
 }
 
-// Putting the data into cache could potentially fail. That's okay if it does
-// happen, we have no way of handling that and we might as well just keep
-// serving files.
-#[allow(unused_must_use)]
 /// Compresses data with zstd encoding.
 ///
 /// The compressed files are cached based on the hash values provided.
 /// Since we already have the hashes precomputed in rust-embed and rust-embed-for-web,
 /// we just reuse that instead of trying to hash the data this function gets.
 #[cfg(feature = "compression-zstd")]
-pub(crate) fn compress_data_zstd(hash: &str, data: &[u8]) -> Vec<u8> {
+pub(crate) fn compress_data_zstd(hash: &str, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
     lazy_static! {
-        static ref CACHED_ZSTD_DATA: RwLock<HashMap<String, Vec<u8>>> = RwLock::new(HashMap::new());
+        static ref CACHED_ZSTD_DATA: RwLock<BoundedCache<String, Vec<u8>>> =
+            RwLock::new(BoundedCache::new(MAX_CACHED_BYTES));
     }
 
-    if let Some(data_zstd) = CACHED_ZSTD_DATA
-        .read()
-        .ok()
-        .and_then(|cached| cached.get(hash).map(ToOwned::to_owned))
-    {
-        return data_zstd;
-    }
+    compress_cached(&CACHED_ZSTD_DATA, hash, data, encode_zstd)
+}
 
-    let compressed = zstd::encode_all(data, 0).expect("Failed to compress zstd data");
-    CACHED_ZSTD_DATA
-        .write()
-        .map(|mut cached| cached.insert(hash.to_string(), compressed.clone()));
-    compressed
+/// Compresses data with gzip encoding, without caching it.
+pub(crate) fn encode_gzip(data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .and_then(|_| encoder.finish())
+        .map_err(|error| CompressionError::new(Encoding::Gzip, error))
+}
+
+/// Compresses data with brotli encoding, without caching it.
+pub(crate) fn encode_br(data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    let mut data_read = BufReader::new(data);
+    let mut compressed: Vec<u8> = Vec::new();
+    brotli::BrotliCompress(
+        &mut data_read,
+        &mut compressed,
+        &BrotliEncoderParams::default(),
+    )
+    .map_err(|error| CompressionError::new(Encoding::Brotli, error))?;
+    Ok(compressed)
+}
+
+/// Compresses data with zstd encoding, without caching it.
+#[cfg(feature = "compression-zstd")]
+pub(crate) fn encode_zstd(data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    zstd::encode_all(data, 0).map_err(|error| CompressionError::new(Encoding::Zstd, error))
 }
 
 #[allow(unused_imports)]
 mod test {
-    use crate::compress::is_well_known_compressible_mime_type;
+    use crate::bounded::BoundedCache;
+    use crate::compress::{
+        compress_cached, is_well_known_compressible_mime_type, CompressionError,
+    };
+    use crate::Encoding;
     use crate::{compress_data_br, compress_data_gzip};
     use std::io::Write;
+    use std::sync::RwLock;
     use std::time::Instant;
 
     #[test]
@@ -181,6 +252,40 @@ mod test {
         assert!(is_well_known_compressible_mime_type("application/xml"))
     }
 
+    #[test]
+    fn parameters_are_ignored() {
+        assert!(is_well_known_compressible_mime_type(
+            "text/html; charset=utf-8"
+        ));
+        assert!(is_well_known_compressible_mime_type(
+            "application/json ; charset=utf-8"
+        ));
+        assert!(!is_well_known_compressible_mime_type(
+            "image/png; charset=utf-8"
+        ));
+    }
+
+    #[test]
+    fn mime_type_case_is_ignored() {
+        assert!(is_well_known_compressible_mime_type("Text/HTML"))
+    }
+
+    #[test]
+    fn web_manifest_and_svg_are_compressible() {
+        assert!(is_well_known_compressible_mime_type(
+            "application/manifest+json"
+        ));
+        assert!(is_well_known_compressible_mime_type("image/svg+xml"))
+    }
+
+    #[test]
+    fn mime_type_must_match_entirely() {
+        assert!(!is_well_known_compressible_mime_type(
+            "application/xml-dtd-but-not-really"
+        ));
+        assert!(!is_well_known_compressible_mime_type("x-application/json"));
+    }
+
     #[test]
     fn jpg_file_not_compressible() {
         assert!(!is_well_known_compressible_mime_type("image/jpeg"))
@@ -194,7 +299,7 @@ mod test {
     #[test]
     fn gzip_roundtrip() {
         let source = b"x123";
-        let compressed = compress_data_gzip("foo", source);
+        let compressed = compress_data_gzip("foo", source).unwrap();
         let mut decompressed = Vec::new();
         flate2::write::GzDecoder::new(&mut decompressed)
             .write_all(&compressed)
@@ -205,7 +310,7 @@ mod test {
     #[test]
     fn br_roundtrip() {
         let source = b"x123";
-        let compressed = compress_data_br("bar", source);
+        let compressed = compress_data_br("bar", source).unwrap();
         let mut decompressed = Vec::new();
         brotli::BrotliDecompress(&mut &compressed[..], &mut decompressed).unwrap();
         assert_eq!(source, &decompressed[..]);
@@ -216,10 +321,10 @@ mod test {
         let source = b"Et quos non sed magnam reiciendis praesentium quod libero. Architecto optio tempora iure aspernatur rerum voluptatem quas. Eos ut atque quas perspiciatis dolorem quidem. Cum et quo et. Voluptatum ut est id eligendi illum inventore. Est non rerum vel rem. Molestiae similique alias nihil harum qui. Consectetur et dolores autem. Magnam et saepe ad reprehenderit. Repellendus vel excepturi eaque esse error. Deserunt est impedit totam nostrum sunt. Eligendi magnam distinctio odit iste molestias est id. Deserunt odit similique magnam repudiandae aut saepe. Dolores laboriosam consectetur quos dolores ea. Non quod veniam quisquam molestias aut deserunt tempora. Mollitia consequuntur facilis doloremque provident eligendi similique possimus. Deleniti facere quam fugiat porro. Tenetur cupiditate eum consequatur beatae dolorum. Veniam voluptatem qui eum quasi corrupti. Quis necessitatibus maxime eum numquam ipsam ducimus expedita maiores. Aliquid voluptas non aut. Tempore dicta ut aperiam ipsum ut et esse explicabo.";
 
         let first_start = Instant::now();
-        compress_data_gzip("lorem", source);
+        compress_data_gzip("lorem", source).unwrap();
         let first = first_start.elapsed();
         let second_start = Instant::now();
-        compress_data_gzip("lorem", source);
+        compress_data_gzip("lorem", source).unwrap();
         let second = second_start.elapsed();
 
         // Check that the second call was faster
@@ -231,10 +336,10 @@ mod test {
         let source = b"Et quos non sed magnam reiciendis praesentium quod libero. Architecto optio tempora iure aspernatur rerum voluptatem quas. Eos ut atque quas perspiciatis dolorem quidem. Cum et quo et. Voluptatum ut est id eligendi illum inventore. Est non rerum vel rem. Molestiae similique alias nihil harum qui. Consectetur et dolores autem. Magnam et saepe ad reprehenderit. Repellendus vel excepturi eaque esse error. Deserunt est impedit totam nostrum sunt. Eligendi magnam distinctio odit iste molestias est id. Deserunt odit similique magnam repudiandae aut saepe. Dolores laboriosam consectetur quos dolores ea. Non quod veniam quisquam molestias aut deserunt tempora. Mollitia consequuntur facilis doloremque provident eligendi similique possimus. Deleniti facere quam fugiat porro. Tenetur cupiditate eum consequatur beatae dolorum. Veniam voluptatem qui eum quasi corrupti. Quis necessitatibus maxime eum numquam ipsam ducimus expedita maiores. Aliquid voluptas non aut. Tempore dicta ut aperiam ipsum ut et esse explicabo.";
 
         let first_start = Instant::now();
-        compress_data_br("lorem-br", source);
+        compress_data_br("lorem-br", source).unwrap();
         let first = first_start.elapsed();
         let second_start = Instant::now();
-        compress_data_br("lorem-br", source);
+        compress_data_br("lorem-br", source).unwrap();
         let second = second_start.elapsed();
 
         // Check that the second call was faster
@@ -245,7 +350,7 @@ mod test {
     #[cfg(feature = "compression-zstd")]
     fn zstd_roundtrip() {
         let source = b"x123";
-        let compressed = crate::compress_data_zstd("foo", source);
+        let compressed = crate::compress_data_zstd("foo", source).unwrap();
         let decompressed = zstd::decode_all(&compressed[..]).unwrap();
         assert_eq!(source, &decompressed[..]);
     }
@@ -256,13 +361,35 @@ mod test {
         let source = b"Et quos non sed magnam reiciendis praesentium quod libero. Architecto optio tempora iure aspernatur rerum voluptatem quas. Eos ut atque quas perspiciatis dolorem quidem. Cum et quo et. Voluptatum ut est id eligendi illum inventore. Est non rerum vel rem. Molestiae similique alias nihil harum qui. Consectetur et dolores autem. Magnam et saepe ad reprehenderit. Repellendus vel excepturi eaque esse error. Deserunt est impedit totam nostrum sunt. Eligendi magnam distinctio odit iste molestias est id. Deserunt odit similique magnam repudiandae aut saepe. Dolores laboriosam consectetur quos dolores ea. Non quod veniam quisquam molestias aut deserunt tempora. Mollitia consequuntur facilis doloremque provident eligendi similique possimus. Deleniti facere quam fugiat porro. Tenetur cupiditate eum consequatur beatae dolorum. Veniam voluptatem qui eum quasi corrupti. Quis necessitatibus maxime eum numquam ipsam ducimus expedita maiores. Aliquid voluptas non aut. Tempore dicta ut aperiam ipsum ut et esse explicabo.";
 
         let first_start = Instant::now();
-        crate::compress_data_zstd("lorem-zstd", source);
+        crate::compress_data_zstd("lorem-zstd", source).unwrap();
         let first = first_start.elapsed();
         let second_start = Instant::now();
-        crate::compress_data_zstd("lorem-zstd", source);
+        crate::compress_data_zstd("lorem-zstd", source).unwrap();
         let second = second_start.elapsed();
 
         // Check that the second call was faster
         assert!(first > second);
     }
+
+    #[test]
+    fn failed_compression_is_not_cached() {
+        let cache = RwLock::new(BoundedCache::new(1024));
+        let failing = |_: &[u8]| {
+            Err(CompressionError::new(
+                Encoding::Brotli,
+                std::io::Error::other("encoder failed"),
+            ))
+        };
+        let error = compress_cached(&cache, "failing", b"x123", failing).unwrap_err();
+        assert_eq!(error.encoding(), Encoding::Brotli);
+        assert_eq!(
+            error.to_string(),
+            "failed to compress with br: encoder failed"
+        );
+        assert!(cache.read().unwrap().get("failing").is_none());
+
+        let compressed = compress_cached(&cache, "failing", b"x123", |data| Ok(data.to_vec()));
+        assert_eq!(compressed.unwrap(), b"x123");
+        assert!(compress_cached(&cache, "failing", b"x123", failing).is_ok());
+    }
 }
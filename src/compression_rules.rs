@@ -0,0 +1,212 @@
+use crate::{compress::Compress, glob::Glob, parse::parse_mime_essence};
+
+/// A content encoding that responses can be compressed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// `zstd`, only used with the `compression-zstd` feature.
+    Zstd,
+    /// `br`
+    Brotli,
+    /// `gzip`
+    Gzip,
+}
+
+impl Encoding {
+    /// All the encodings, in the order they are preferred.
+    pub(crate) const ALL: &'static [Encoding] = &[Encoding::Zstd, Encoding::Brotli, Encoding::Gzip];
+
+    /// The name of the encoding in `Accept-Encoding` and `Content-Encoding`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Encoding::Zstd => "zstd",
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+}
+
+/// Rules for compressing some files differently from the rest.
+///
+/// Each rule matches either the path of the request, or the mime type of the
+/// file, and sets the compression option and the encodings to use for it.
+/// Like `PathRules`, the last rule that matches decides. Files that don't
+/// match any rule use the compression option of the response.
+///
+/// ```
+/// use actix_web_rust_embed_responder::{Compress, CompressionRule, CompressionRules, Encoding};
+///
+/// let rules = CompressionRules::new()
+///     .rule(CompressionRule::path("*.wasm", Compress::Always))
+///     .rule(CompressionRule::path("/static/**", Compress::IfPrecompressed))
+///     .rule(CompressionRule::path("/downloads/**", Compress::Never))
+///     .rule(CompressionRule::mime("text/*", Compress::Always).encodings([Encoding::Gzip]));
+/// ```
+///
+/// Path patterns are globs, with the same syntax as `PathRules`, matched
+/// against the path of the request URL. Mime patterns are a mime type like
+/// `text/html`, or one with a wildcard like `text/*` or `*/*`.
+#[derive(Clone, Debug, Default)]
+pub struct CompressionRules {
+    rules: Vec<CompressionRule>,
+}
+
+/// One of the `CompressionRules`.
+#[derive(Clone, Debug)]
+pub struct CompressionRule {
+    matcher: Matcher,
+    compress: Compress,
+    encodings: Option<Vec<Encoding>>,
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Path(Glob),
+    Mime(MimePattern),
+}
+
+impl CompressionRules {
+    /// No rules, all files use the compression option of the response.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule, which takes precedence over the earlier ones.
+    pub fn rule(mut self, rule: CompressionRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// The compression option and encodings for a file, if any rule
+    /// matches it.
+    pub(crate) fn for_file(
+        &self,
+        path: &str,
+        mime_type: Option<&str>,
+    ) -> Option<(Compress, &[Encoding])> {
+        let path = path.trim_start_matches('/');
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| match &rule.matcher {
+                Matcher::Path(glob) => glob.is_match(path),
+                Matcher::Mime(pattern) => mime_type
+                    .map(|mime_type| pattern.is_match(mime_type))
+                    .unwrap_or(false),
+            })
+            .map(|rule| {
+                let encodings = rule.encodings.as_deref().unwrap_or(Encoding::ALL);
+                (rule.compress, encodings)
+            })
+    }
+}
+
+impl CompressionRule {
+    /// Use this compression option for the paths matching the glob pattern.
+    pub fn path(pattern: impl AsRef<str>, compress: Compress) -> Self {
+        CompressionRule {
+            matcher: Matcher::Path(Glob::new(pattern.as_ref())),
+            compress,
+            encodings: None,
+        }
+    }
+
+    /// Use this compression option for the files with a matching mime type.
+    pub fn mime(pattern: impl AsRef<str>, compress: Compress) -> Self {
+        CompressionRule {
+            matcher: Matcher::Mime(MimePattern::new(pattern.as_ref())),
+            compress,
+            encodings: None,
+        }
+    }
+
+    /// Only compress the matching files with these encodings. By default,
+    /// all encodings the client accepts can be used.
+    pub fn encodings(mut self, encodings: impl IntoIterator<Item = Encoding>) -> Self {
+        self.encodings = Some(encodings.into_iter().collect());
+        self
+    }
+}
+
+/// A mime type like `text/html`, `text/*`, or `*/*`.
+#[derive(Clone, Debug)]
+struct MimePattern {
+    type_: String,
+    /// `None` matches any subtype.
+    subtype: Option<String>,
+}
+
+impl MimePattern {
+    fn new(pattern: &str) -> Self {
+        let pattern = parse_mime_essence(pattern).to_ascii_lowercase();
+        let (type_, subtype) = pattern.split_once('/').unwrap_or((&pattern, "*"));
+        MimePattern {
+            type_: type_.to_string(),
+            subtype: (subtype != "*").then(|| subtype.to_string()),
+        }
+    }
+
+    fn is_match(&self, mime_type: &str) -> bool {
+        let mime_type = parse_mime_essence(mime_type);
+        let (type_, subtype) = mime_type.split_once('/').unwrap_or((mime_type, ""));
+        (self.type_ == "*" || self.type_.eq_ignore_ascii_case(type_))
+            && self
+                .subtype
+                .as_ref()
+                .map(|expected| expected.eq_ignore_ascii_case(subtype))
+                .unwrap_or(true)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::{
+        compress::Compress,
+        compression_rules::{CompressionRule, CompressionRules, Encoding},
+    };
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let rules = CompressionRules::new()
+            .rule(CompressionRule::path("*.wasm", Compress::Always))
+            .rule(CompressionRule::path("/downloads/**", Compress::Never));
+        assert_eq!(
+            rules.for_file("/app.wasm", None),
+            Some((Compress::Always, Encoding::ALL))
+        );
+        assert_eq!(
+            rules.for_file("/downloads/app.wasm", None),
+            Some((Compress::Never, Encoding::ALL))
+        );
+        assert_eq!(rules.for_file("/index.html", None), None);
+    }
+
+    #[test]
+    fn mime_patterns_match_types() {
+        let rules = CompressionRules::new()
+            .rule(CompressionRule::mime("text/*", Compress::Always).encodings([Encoding::Gzip]))
+            .rule(CompressionRule::mime(
+                "Image/SVG+XML",
+                Compress::IfWellKnown,
+            ));
+        assert_eq!(
+            rules.for_file("/a", Some("text/html; charset=utf-8")),
+            Some((Compress::Always, &[Encoding::Gzip][..]))
+        );
+        assert_eq!(
+            rules.for_file("/a", Some("image/svg+xml")),
+            Some((Compress::IfWellKnown, Encoding::ALL))
+        );
+        assert_eq!(rules.for_file("/a", Some("image/png")), None);
+        assert_eq!(rules.for_file("/a", None), None);
+    }
+
+    #[test]
+    fn any_mime_type_matches_wildcards() {
+        let rules = CompressionRules::new().rule(CompressionRule::mime("*/*", Compress::Never));
+        assert_eq!(
+            rules.for_file("/a", Some("application/json")),
+            Some((Compress::Never, Encoding::ALL))
+        );
+        assert_eq!(rules.for_file("/a", None), None);
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use actix_web::HttpRequest;
+
+use crate::{
+    cache::CachePolicy,
+    compress::{Compress, CompressionError, CompressionErrorHook},
+    compression_rules::CompressionRules,
+    error_pages::ErrorPages,
+    mime::MimeOverrides,
+    sniff::MimeFallback,
+};
+
+/// Settings for all the responses in an app.
+///
+/// Add this to your app with `app_data`, and every `EmbedResponse` and
+/// `EmbedService` in it will use these settings:
+///
+/// ```
+/// use actix_web::App;
+/// use actix_web_rust_embed_responder::{CachePolicy, Compress, EmbedConfig};
+///
+/// App::new().app_data(
+///     EmbedConfig::new()
+///         .use_compression(Compress::IfWellKnown)
+///         .use_cache_policy(CachePolicy::MaxAge(3600)),
+/// );
+/// ```
+///
+/// Settings on a response, like `.use_compression()`, or on a service take
+/// precedence over the config. The error pages are only used by
+/// `EmbedService`, since a response doesn't know which embed to find them in.
+#[derive(Clone, Debug, Default)]
+pub struct EmbedConfig {
+    pub(crate) compress: Compress,
+    pub(crate) compression_rules: Option<Arc<CompressionRules>>,
+    pub(crate) cache: CachePolicy,
+    pub(crate) mime_fallback: MimeFallback,
+    pub(crate) mime_overrides: Option<Arc<MimeOverrides>>,
+    pub(crate) error_pages: ErrorPages,
+    pub(crate) compression_error_hook: Option<CompressionErrorHook>,
+}
+
+impl EmbedConfig {
+    /// The same settings as a response without any options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the compression option to use for all files. Please see the
+    /// Compress type for allowed options.
+    pub fn use_compression(mut self, option: Compress) -> Self {
+        self.compress = option;
+        self
+    }
+
+    /// Set rules for compressing some files differently. Please see the
+    /// CompressionRules type for details.
+    pub fn use_compression_rules(mut self, rules: impl Into<Arc<CompressionRules>>) -> Self {
+        self.compression_rules = Some(rules.into());
+        self
+    }
+
+    /// Call this function when compressing a response fails, for example to
+    /// log the error. The response is sent uncompressed either way.
+    pub fn on_compression_error(
+        mut self,
+        hook: impl Fn(&CompressionError) + Send + Sync + 'static,
+    ) -> Self {
+        self.compression_error_hook = Some(CompressionErrorHook::new(hook));
+        self
+    }
+
+    /// Set the caching option to use for all files. Please see the
+    /// CachePolicy type for allowed options.
+    pub fn use_cache_policy(mut self, option: CachePolicy) -> Self {
+        self.cache = option;
+        self
+    }
+
+    /// Set what to send as the `Content-Type` if the mime type of a file is
+    /// not known. Please see the MimeFallback type for allowed options.
+    pub fn use_mime_fallback(mut self, option: MimeFallback) -> Self {
+        self.mime_fallback = option;
+        self
+    }
+
+    /// Set the mime type overrides and charset to use for all files. Please
+    /// see the MimeOverrides type for details.
+    pub fn use_mime_overrides(mut self, overrides: impl Into<Arc<MimeOverrides>>) -> Self {
+        self.mime_overrides = Some(overrides.into());
+        self
+    }
+
+    /// Set the pages to send for errors from an `EmbedService`. Please see
+    /// the ErrorPages type for the details.
+    pub fn error_pages(mut self, pages: ErrorPages) -> Self {
+        self.error_pages = pages;
+        self
+    }
+}
+
+/// The config added to the app, if there is one.
+pub(crate) fn embed_config(req: &HttpRequest) -> Option<&EmbedConfig> {
+    req.app_data::<EmbedConfig>()
+}
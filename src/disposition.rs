@@ -0,0 +1,84 @@
+use actix_web::http::header::{
+    Charset, ContentDisposition, DispositionParam, DispositionType, ExtendedValue,
+};
+
+/// A plain ASCII version of the filename, for clients that don't support
+/// `filename*`. Anything that can't be sent safely is replaced.
+fn ascii_filename(filename: &str) -> String {
+    filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() && c != '/' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Builds a `Content-Disposition` header for the filename.
+///
+/// Every client gets an ASCII `filename`, and if the filename has any other
+/// characters the original is also sent as an RFC 8187 encoded `filename*`,
+/// which takes priority in clients that support it.
+pub(crate) fn content_disposition(
+    disposition: DispositionType,
+    filename: &str,
+) -> ContentDisposition {
+    let fallback = ascii_filename(filename);
+    let mut parameters = Vec::with_capacity(2);
+    if fallback != filename {
+        parameters.push(DispositionParam::FilenameExt(ExtendedValue {
+            charset: Charset::Ext("UTF-8".to_string()),
+            language_tag: None,
+            value: filename.as_bytes().to_vec(),
+        }));
+    }
+    parameters.insert(0, DispositionParam::Filename(fallback));
+    ContentDisposition {
+        disposition,
+        parameters,
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::disposition::content_disposition;
+    use actix_web::http::header::DispositionType;
+
+    #[test]
+    fn ascii_filename_is_quoted() {
+        assert_eq!(
+            content_disposition(DispositionType::Attachment, "report 2024.pdf").to_string(),
+            r#"attachment; filename="report 2024.pdf""#
+        );
+        assert_eq!(
+            content_disposition(DispositionType::Inline, "a.txt").to_string(),
+            r#"inline; filename="a.txt""#
+        );
+    }
+
+    #[test]
+    fn quotes_are_escaped() {
+        assert_eq!(
+            content_disposition(DispositionType::Attachment, r#"say "hi".txt"#).to_string(),
+            r#"attachment; filename="say \"hi\".txt""#
+        );
+    }
+
+    #[test]
+    fn non_ascii_filename_is_encoded() {
+        assert_eq!(
+            content_disposition(DispositionType::Attachment, "rapor-ğüş.pdf").to_string(),
+            "attachment; filename=\"rapor-___.pdf\"; filename*=UTF-8''rapor%2D%C4%9F%C3%BC%C5%9F.pdf"
+        );
+    }
+
+    #[test]
+    fn unsafe_characters_are_replaced() {
+        let header = content_disposition(DispositionType::Attachment, "../a\r\nb.txt").to_string();
+        assert!(header.starts_with(r#"attachment; filename=".._a__b.txt""#));
+        assert!(!header.contains('\n'));
+    }
+}
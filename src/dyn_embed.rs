@@ -0,0 +1,224 @@
+use std::{borrow::Cow, io};
+
+use actix_web::{body::MessageBody, http::header::HeaderValue, web::Bytes};
+
+use crate::embed::{EmbedRespondable, EmbedResponse};
+
+/// A version of `EmbedRespondable` that can be used as a trait object.
+///
+/// This is implemented for every `EmbedRespondable`, you shouldn't need to
+/// implement it yourself. The methods are prefixed with `dyn_` so they don't
+/// clash with the ones of `EmbedRespondable`.
+pub trait DynEmbedRespondable {
+    /// The contents of the file.
+    fn dyn_data(&self) -> Bytes;
+    /// The contents of the file compressed with gzip, if it was precompressed.
+    fn dyn_data_gzip(&self) -> Option<Bytes>;
+    /// The contents of the file compressed with brotli, if it was precompressed.
+    fn dyn_data_br(&self) -> Option<Bytes>;
+    /// The contents of the file compressed with zstd, if it was precompressed.
+    fn dyn_data_zstd(&self) -> Option<Bytes>;
+    /// The UNIX timestamp of when the file was last modified.
+    fn dyn_last_modified_timestamp(&self) -> Option<i64>;
+    /// The rfc2822 encoded last modified date.
+    fn dyn_last_modified(&self) -> Option<String>;
+    /// The ETag value for the file.
+    fn dyn_etag(&self) -> String;
+    /// The mime type for the file, if one has been guessed.
+    fn dyn_mime_type(&self) -> Option<String>;
+    /// The path of the file inside the embed, if it is known.
+    fn dyn_name(&self) -> Option<Cow<'_, str>>;
+    /// Whether the mime type was set for this file on purpose.
+    fn dyn_has_explicit_mime_type(&self) -> bool;
+    /// The size of the file in bytes.
+    fn dyn_size(&self) -> usize;
+    /// Reads the data of the file, if it isn't read yet.
+    fn dyn_load_data(&self) -> io::Result<()>;
+    /// Identifies the contents of the file for the caches.
+    fn dyn_cache_key(&self) -> Cow<'_, str>;
+    /// The ETag value for the file, ready to be used as a header.
+    fn dyn_etag_header(&self) -> Option<HeaderValue>;
+    /// The last modified date, ready to be used as a header.
+    fn dyn_last_modified_header(&self) -> Option<HeaderValue>;
+}
+
+/// Most bodies can be turned into `Bytes` without copying, like static data.
+fn into_bytes<B: MessageBody + AsRef<[u8]>>(body: B) -> Bytes {
+    body.try_into_bytes()
+        .unwrap_or_else(|body| Bytes::copy_from_slice(body.as_ref()))
+}
+
+impl<T: EmbedRespondable> DynEmbedRespondable for T {
+    fn dyn_data(&self) -> Bytes {
+        into_bytes(self.data())
+    }
+
+    fn dyn_data_gzip(&self) -> Option<Bytes> {
+        self.data_gzip().map(into_bytes)
+    }
+
+    fn dyn_data_br(&self) -> Option<Bytes> {
+        self.data_br().map(into_bytes)
+    }
+
+    fn dyn_data_zstd(&self) -> Option<Bytes> {
+        self.data_zstd().map(into_bytes)
+    }
+
+    fn dyn_last_modified_timestamp(&self) -> Option<i64> {
+        self.last_modified_timestamp()
+    }
+
+    fn dyn_last_modified(&self) -> Option<String> {
+        self.last_modified().map(|value| value.as_ref().to_string())
+    }
+
+    fn dyn_etag(&self) -> String {
+        self.etag().as_ref().to_string()
+    }
+
+    fn dyn_mime_type(&self) -> Option<String> {
+        self.mime_type().map(|value| value.as_ref().to_string())
+    }
+
+    fn dyn_name(&self) -> Option<Cow<'_, str>> {
+        self.name()
+    }
+
+    fn dyn_has_explicit_mime_type(&self) -> bool {
+        self.has_explicit_mime_type()
+    }
+
+    fn dyn_size(&self) -> usize {
+        self.size()
+    }
+
+    fn dyn_load_data(&self) -> io::Result<()> {
+        self.load_data()
+    }
+
+    fn dyn_cache_key(&self) -> Cow<'_, str> {
+        self.cache_key()
+    }
+
+    fn dyn_etag_header(&self) -> Option<HeaderValue> {
+        self.etag_header()
+    }
+
+    fn dyn_last_modified_header(&self) -> Option<HeaderValue> {
+        self.last_modified_header()
+    }
+}
+
+/// Any file that can be responded with, from any kind of embed.
+///
+/// You can create one with `DynEmbedFile::new`, or turn a response into a
+/// `DynEmbedResponse` with `.into_dyn()`.
+pub struct DynEmbedFile(Box<dyn DynEmbedRespondable>);
+
+impl DynEmbedFile {
+    /// Box a file from any embed.
+    pub fn new(file: impl EmbedRespondable + 'static) -> Self {
+        DynEmbedFile(Box::new(file))
+    }
+}
+
+impl EmbedRespondable for DynEmbedFile {
+    type Data = Bytes;
+    type DataGzip = Bytes;
+    type DataBr = Bytes;
+    type DataZstd = Bytes;
+    type MimeType = String;
+    type ETag = String;
+    type LastModified = String;
+
+    fn data(&self) -> Self::Data {
+        self.0.dyn_data()
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        self.0.dyn_data_gzip()
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        self.0.dyn_data_br()
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        self.0.dyn_data_zstd()
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        self.0.dyn_last_modified_timestamp()
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        self.0.dyn_last_modified()
+    }
+
+    fn etag(&self) -> Self::ETag {
+        self.0.dyn_etag()
+    }
+
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        self.0.dyn_mime_type()
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.0.dyn_name()
+    }
+
+    fn has_explicit_mime_type(&self) -> bool {
+        self.0.dyn_has_explicit_mime_type()
+    }
+
+    fn size(&self) -> usize {
+        self.0.dyn_size()
+    }
+
+    fn load_data(&self) -> io::Result<()> {
+        self.0.dyn_load_data()
+    }
+
+    fn cache_key(&self) -> Cow<'_, str> {
+        self.0.dyn_cache_key()
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        self.0.dyn_etag_header()
+    }
+
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        self.0.dyn_last_modified_header()
+    }
+}
+
+/// A response for a file from any kind of embed.
+///
+/// Handlers can return this type no matter where the file came from, for
+/// example a `rust-embed` file on one branch and a `rust-embed-for-web` file
+/// on another, without needing `EmbedableFileResponse` to switch between
+/// debug and release builds.
+///
+/// ```ignore
+/// async fn handler(path: web::Path<String>) -> DynEmbedResponse {
+///     match Theme::get(&path) {
+///         Some(file) => file.into_response().into_dyn(),
+///         None => Base::get(&path).into_response().into_dyn(),
+///     }
+/// }
+/// ```
+pub type DynEmbedResponse = EmbedResponse<DynEmbedFile>;
+
+impl<T: EmbedRespondable + 'static> EmbedResponse<T> {
+    /// Turn this into a response that can hold a file from any embed. The
+    /// options set on this response are kept.
+    pub fn into_dyn(self) -> DynEmbedResponse {
+        EmbedResponse {
+            file: self.file.map(DynEmbedFile::new),
+            options: self.options,
+            not_found_page: self.not_found_page.map(DynEmbedFile::new),
+            status: self.status,
+        }
+    }
+}
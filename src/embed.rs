@@ -1,14 +1,28 @@
 use actix_web::{
     body::{BoxBody, MessageBody},
-    http::Method,
-    HttpRequest, HttpResponse, Responder,
+    http::{
+        header::{self, ContentDisposition, DispositionType, HeaderValue},
+        Method, StatusCode,
+    },
+    HttpRequest, HttpResponse, HttpResponseBuilder, Responder,
 };
+use percent_encoding::percent_decode_str;
+use std::{borrow::Cow, io, sync::Arc};
 
 #[cfg(feature = "compression-zstd")]
 use crate::compress_data_zstd;
 use crate::{
-    compress::Compress, compress_data_br, compress_data_gzip, helper::accepts_encoding,
-    is_well_known_compressible_mime_type, parse::parse_if_none_match_value,
+    cache::CachePolicy,
+    compress::{Compress, CompressionError, CompressionErrorHook},
+    compress_data_br, compress_data_gzip,
+    compression_rules::{CompressionRules, Encoding},
+    config::{embed_config, EmbedConfig},
+    disposition::content_disposition,
+    helper::accepts_encoding,
+    is_well_known_compressible_mime_type,
+    mime::{MimeOverrides, DEFAULT_MIME_OVERRIDES},
+    parse::parse_if_none_match_value,
+    sniff::{sniff_mime_type_cached, MimeFallback, OCTET_STREAM},
 };
 
 /// A common trait used internally to create HTTP responses.
@@ -48,6 +62,70 @@ pub trait EmbedRespondable {
     fn etag(&self) -> Self::ETag;
     /// The mime type for the file, if one has been guessed.
     fn mime_type(&self) -> Option<Self::MimeType>;
+
+    /// The path of the file inside the embed, if it is known.
+    ///
+    /// This is used to check for mime type overrides based on the file
+    /// extension. The default implementation returns `None`.
+    fn name(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+
+    /// Whether the mime type was set for this file on purpose, instead of
+    /// being guessed from its path.
+    ///
+    /// An explicit mime type is used even if there is a mime override for
+    /// the file's extension. The default implementation returns `false`.
+    fn has_explicit_mime_type(&self) -> bool {
+        false
+    }
+
+    /// The size of the file in bytes, like for a directory listing.
+    ///
+    /// The default implementation gets the length of `data()`. Files that
+    /// only read their data when it is needed should override this.
+    fn size(&self) -> usize {
+        self.data().as_ref().len()
+    }
+
+    /// Reads the data of the file, for files that only read it when it is
+    /// needed. This is called before the data is sent, and if it fails the
+    /// response is a `404 Not Found` if the file doesn't exist anymore, or a
+    /// `500 Internal Server Error` otherwise.
+    ///
+    /// The default implementation does nothing, for files that always have
+    /// their data.
+    fn load_data(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Identifies the contents of the file for the caches of compressed data
+    /// and sniffed mime types.
+    ///
+    /// The default implementation uses the ETag. Wrappers that change the
+    /// ETag without changing the data should return the key of the file
+    /// they wrap, so files with the same ETag don't share cached data.
+    fn cache_key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.etag().as_ref().to_string())
+    }
+
+    /// The ETag value for the file, ready to be used as a header.
+    ///
+    /// The default implementation converts the value of `etag()` every time.
+    /// Implementations that can reuse the header value across responses
+    /// should override this.
+    fn etag_header(&self) -> Option<HeaderValue> {
+        HeaderValue::from_str(self.etag().as_ref()).ok()
+    }
+    /// The last modified date, ready to be used as a header.
+    ///
+    /// The default implementation converts the value of `last_modified()`
+    /// every time. Implementations that can reuse the header value across
+    /// responses should override this.
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        self.last_modified()
+            .and_then(|v| HeaderValue::from_str(v.as_ref()).ok())
+    }
 }
 
 /// An opaque wrapper around the embedded file.
@@ -56,7 +134,129 @@ pub trait EmbedRespondable {
 /// or `.into()` to convert an embedded file into an `EmbedResponse`.
 pub struct EmbedResponse<T: EmbedRespondable> {
     pub(crate) file: Option<T>,
-    pub(crate) compress: Compress,
+    pub(crate) options: ResponseOptions,
+    pub(crate) not_found_page: Option<T>,
+    pub(crate) status: Option<StatusCode>,
+}
+
+/// The settings for a response, everything other than the file itself.
+///
+/// The settings that aren't set use the `EmbedConfig` of the app, or the
+/// defaults if there isn't one.
+#[derive(Clone, Default)]
+pub(crate) struct ResponseOptions {
+    pub(crate) compress: Option<Compress>,
+    pub(crate) compression_rules: Option<Arc<CompressionRules>>,
+    pub(crate) mime_fallback: Option<MimeFallback>,
+    pub(crate) mime_overrides: Option<Arc<MimeOverrides>>,
+    pub(crate) disposition: Option<ContentDisposition>,
+    pub(crate) cache: Option<CachePolicy>,
+    pub(crate) compression_error_hook: Option<CompressionErrorHook>,
+}
+
+impl ResponseOptions {
+    /// Fill in the settings that aren't set from the config.
+    pub(crate) fn with_config(mut self, config: Option<&EmbedConfig>) -> Self {
+        if let Some(config) = config {
+            self.compress = self.compress.or(Some(config.compress));
+            self.compression_rules = self
+                .compression_rules
+                .or_else(|| config.compression_rules.clone());
+            self.mime_fallback = self.mime_fallback.or(Some(config.mime_fallback));
+            self.mime_overrides = self
+                .mime_overrides
+                .or_else(|| config.mime_overrides.clone());
+            self.cache = self.cache.or_else(|| Some(config.cache.clone()));
+            self.compression_error_hook = self
+                .compression_error_hook
+                .or_else(|| config.compression_error_hook.clone());
+        }
+        self
+    }
+
+    fn compress(&self) -> Compress {
+        self.compress.unwrap_or_default()
+    }
+
+    fn mime_fallback(&self) -> MimeFallback {
+        self.mime_fallback.unwrap_or_default()
+    }
+
+    fn mime_overrides(&self) -> &MimeOverrides {
+        self.mime_overrides
+            .as_deref()
+            .unwrap_or(&DEFAULT_MIME_OVERRIDES)
+    }
+
+    fn cache_header(&self) -> HeaderValue {
+        match &self.cache {
+            Some(cache) => cache.header_value(),
+            None => CachePolicy::default().header_value(),
+        }
+    }
+}
+
+impl<T: EmbedRespondable> EmbedResponse<T> {
+    pub(crate) fn new(file: Option<T>) -> Self {
+        EmbedResponse {
+            file,
+            options: Default::default(),
+            not_found_page: None,
+            status: None,
+        }
+    }
+
+    pub(crate) fn with_options(file: Option<T>, options: ResponseOptions) -> Self {
+        EmbedResponse {
+            options,
+            ..EmbedResponse::new(file)
+        }
+    }
+}
+
+/// The mime type we'll send, which either came from the file, or was picked
+/// as an override or a fallback.
+enum ResponseMimeType<'a, M: AsRef<str>> {
+    File(M),
+    Override(&'a str),
+    Fallback(&'static str),
+}
+
+impl<M: AsRef<str>> AsRef<str> for ResponseMimeType<'_, M> {
+    fn as_ref(&self) -> &str {
+        match self {
+            ResponseMimeType::File(mime_type) => mime_type.as_ref(),
+            ResponseMimeType::Override(mime_type) => mime_type,
+            ResponseMimeType::Fallback(mime_type) => mime_type,
+        }
+    }
+}
+
+fn response_mime_type<'a, T: EmbedRespondable>(
+    file: &T,
+    options: &'a ResponseOptions,
+) -> Option<ResponseMimeType<'a, T::MimeType>> {
+    if file.has_explicit_mime_type() {
+        if let Some(mime_type) = file.mime_type() {
+            return Some(ResponseMimeType::File(mime_type));
+        }
+    }
+    if let Some(mime_type) = file
+        .name()
+        .and_then(|name| options.mime_overrides().for_path(name.as_ref()))
+    {
+        return Some(ResponseMimeType::Override(mime_type));
+    }
+    if let Some(mime_type) = file.mime_type() {
+        return Some(ResponseMimeType::File(mime_type));
+    }
+    match options.mime_fallback() {
+        MimeFallback::Omit => None,
+        MimeFallback::OctetStream => Some(ResponseMimeType::Fallback(OCTET_STREAM)),
+        MimeFallback::Sniff => Some(ResponseMimeType::Fallback(
+            sniff_mime_type_cached(&file.cache_key(), file.data().as_ref()).unwrap_or(OCTET_STREAM),
+        )),
+    }
 }
 
 enum ShouldCompress {
@@ -66,94 +266,167 @@ enum ShouldCompress {
     No,
 }
 
-fn should_compress<T: EmbedRespondable>(
+/// Pick the encoding to send the file with, from the ones the client accepts
+/// and the compression rules allow.
+fn negotiate_encoding<T: EmbedRespondable>(
     req: &HttpRequest,
     file: &T,
-    compress: &Compress,
+    mime_type: Option<&str>,
+    options: &ResponseOptions,
 ) -> ShouldCompress {
-    let should_compress_for_encoding =
-        |is_precompressed_for_encoding: bool, mime_type: Option<T::MimeType>, encoding: &str| {
-            accepts_encoding(req, encoding)
-                && match compress {
-                    Compress::Never => false,
-                    Compress::IfPrecompressed => is_precompressed_for_encoding,
-                    Compress::IfWellKnown => mime_type
-                        .map(|v| is_well_known_compressible_mime_type(v.as_ref()))
-                        .unwrap_or(false),
-                    Compress::Always => true,
-                }
+    let (compress, encodings) = options
+        .compression_rules
+        .as_ref()
+        .and_then(|rules| {
+            let path = percent_decode_str(req.path()).decode_utf8_lossy();
+            rules.for_file(&path, mime_type)
+        })
+        .unwrap_or((options.compress(), Encoding::ALL));
+
+    for &encoding in encodings {
+        let is_precompressed = match encoding {
+            // Without the feature, zstd can't be sent even if the client accepts it
+            Encoding::Zstd if !cfg!(feature = "compression-zstd") => continue,
+            Encoding::Zstd => file.data_zstd().is_some(),
+            Encoding::Brotli => file.data_br().is_some(),
+            Encoding::Gzip => file.data_gzip().is_some(),
         };
+        let should_compress = accepts_encoding(req, encoding.name())
+            && match compress {
+                Compress::Never => false,
+                Compress::IfPrecompressed => is_precompressed,
+                Compress::IfWellKnown => mime_type
+                    .map(is_well_known_compressible_mime_type)
+                    .unwrap_or(false),
+                Compress::Always => true,
+            };
+        if should_compress {
+            return match encoding {
+                Encoding::Zstd => ShouldCompress::Zstd,
+                Encoding::Brotli => ShouldCompress::Brotli,
+                Encoding::Gzip => ShouldCompress::Gzip,
+            };
+        }
+    }
+    ShouldCompress::No
+}
+
+/// Send a file as the body of an error response.
+///
+/// Error pages stand in for a file that doesn't exist, or a request that
+/// can't be served. They are never cached, and don't have an ETag or a last
+/// modified date so that clients can't revalidate them.
+pub(crate) fn send_error_page<T: EmbedRespondable>(
+    req: &HttpRequest,
+    page: &T,
+    options: &ResponseOptions,
+    status: StatusCode,
+) -> HttpResponse {
+    let options = ResponseOptions {
+        cache: Some(CachePolicy::NoStore),
+        disposition: None,
+        ..options.clone()
+    };
+    let mut resp = send_response(req, page, &options, None);
+    *resp.status_mut() = status;
+    resp.headers_mut().remove(header::LAST_MODIFIED);
+    resp
+}
 
-    if should_compress_for_encoding(file.data_zstd().is_some(), file.mime_type(), "zstd") {
-        ShouldCompress::Zstd
-    } else if should_compress_for_encoding(file.data_br().is_some(), file.mime_type(), "br") {
-        ShouldCompress::Brotli
-    } else if should_compress_for_encoding(file.data_gzip().is_some(), file.mime_type(), "gzip") {
-        ShouldCompress::Gzip
+/// Send a file with a status other than `200 OK`. Error statuses are sent
+/// like an error page, without caching.
+fn send_with_status<T: EmbedRespondable>(
+    req: &HttpRequest,
+    file: &T,
+    options: &ResponseOptions,
+    status: StatusCode,
+) -> HttpResponse {
+    if status.is_success() {
+        let mut resp = send_response(req, file, options, file.etag_header());
+        *resp.status_mut() = status;
+        // Other successful statuses like `201 Created` don't send the file
+        // as a download, so they don't get the disposition.
+        if !matches!(status, StatusCode::OK | StatusCode::PARTIAL_CONTENT) {
+            resp.headers_mut().remove(header::CONTENT_DISPOSITION);
+        }
+        resp
     } else {
-        ShouldCompress::No
+        send_error_page(req, file, options, status)
     }
 }
 
+/// Send the file. `etag` is the value of `file.etag_header()`, which the
+/// caller usually needed already to check the request's conditions.
 fn send_response<T: EmbedRespondable>(
     req: &HttpRequest,
     file: &T,
-    compress: Compress,
+    options: &ResponseOptions,
+    etag: Option<HeaderValue>,
 ) -> HttpResponse {
     let mut resp = HttpResponse::Ok();
 
-    resp.append_header(("ETag", file.etag().as_ref()));
-    if let Some(last_modified) = file.last_modified() {
-        resp.append_header(("Last-Modified", last_modified.as_ref()));
+    if let Some(etag) = etag {
+        resp.insert_header((header::ETAG, etag));
     }
-    if let Some(mime_type) = file.mime_type() {
-        resp.append_header(("Content-Type", mime_type.as_ref()));
+    if let Some(last_modified) = file.last_modified_header() {
+        resp.insert_header((header::LAST_MODIFIED, last_modified));
+    }
+    let mime_type = response_mime_type(file, options);
+    if let Some(mime_type) = &mime_type {
+        match options
+            .mime_overrides()
+            .with_charset_parameter(mime_type.as_ref())
+        {
+            Some(content_type) => resp.append_header(("Content-Type", content_type)),
+            None => resp.append_header(("Content-Type", mime_type.as_ref())),
+        };
     }
 
-    // This doesn't actually mean "no caching", it means revalidate before
-    // using. If we don't add this, web browsers don't try to revalidate assets
-    // like attached scripts and images. The users of this crate may or may not
-    // be using fingerprinting or versioning on their assets, without this their
-    // caching could break.
-    resp.append_header(("Cache-Control", "no-cache"));
+    if let Some(disposition) = &options.disposition {
+        resp.insert_header(disposition.clone());
+    }
+
+    // By default this is `no-cache`, see `CachePolicy` for why.
+    resp.insert_header((header::CACHE_CONTROL, options.cache_header()));
 
     if req.method() == Method::HEAD {
         // For HEAD requests, we only need to send the headers and not the data.
         resp.finish()
     } else {
+        if let Err(error) = file.load_data() {
+            return match error.kind() {
+                io::ErrorKind::NotFound => HttpResponse::NotFound().finish(),
+                _ => HttpResponse::InternalServerError().finish(),
+            };
+        }
         // For GET requests, we do send the file body. Depending on whether the
         // client accepts compressed files or not, we may send the compressed
         // version.
-        let encoding_choice = should_compress(req, file, &compress);
+        let encoding_choice =
+            negotiate_encoding(req, file, mime_type.as_ref().map(AsRef::as_ref), options);
         match encoding_choice {
             #[cfg(feature = "compression-zstd")]
-            ShouldCompress::Zstd => {
-                resp.append_header(("Content-Encoding", "zstd"));
-                match file.data_zstd() {
-                    Some(data_zstd) => resp.body(data_zstd),
-                    None => resp.body(compress_data_zstd(
-                        file.etag().as_ref(),
-                        file.data().as_ref(),
-                    )),
+            ShouldCompress::Zstd => match file.data_zstd() {
+                Some(data_zstd) => {
+                    resp.append_header(("Content-Encoding", "zstd"));
+                    resp.body(data_zstd)
                 }
-            }
-            ShouldCompress::Brotli => {
-                resp.append_header(("Content-Encoding", "br"));
-                match file.data_br() {
-                    Some(data_br) => resp.body(data_br),
-                    None => resp.body(compress_data_br(file.etag().as_ref(), file.data().as_ref())),
+                None => send_compressed(resp, file, options, Encoding::Zstd, compress_data_zstd),
+            },
+            ShouldCompress::Brotli => match file.data_br() {
+                Some(data_br) => {
+                    resp.append_header(("Content-Encoding", "br"));
+                    resp.body(data_br)
                 }
-            }
-            ShouldCompress::Gzip => {
-                resp.append_header(("Content-Encoding", "gzip"));
-                match file.data_gzip() {
-                    Some(data_gzip) => resp.body(data_gzip),
-                    None => resp.body(compress_data_gzip(
-                        file.etag().as_ref(),
-                        file.data().as_ref(),
-                    )),
+                None => send_compressed(resp, file, options, Encoding::Brotli, compress_data_br),
+            },
+            ShouldCompress::Gzip => match file.data_gzip() {
+                Some(data_gzip) => {
+                    resp.append_header(("Content-Encoding", "gzip"));
+                    resp.body(data_gzip)
                 }
-            }
+                None => send_compressed(resp, file, options, Encoding::Gzip, compress_data_gzip),
+            },
             #[cfg(not(feature = "compression-zstd"))]
             ShouldCompress::Zstd => {
                 // This should never happen, but if it does, just serve uncompressed
@@ -164,21 +437,62 @@ fn send_response<T: EmbedRespondable>(
     }
 }
 
+/// Compresses the file on the fly. If that fails, the failure is reported to
+/// the hook and the file is sent uncompressed, so one bad file doesn't take
+/// down the worker.
+fn send_compressed<T: EmbedRespondable>(
+    mut resp: HttpResponseBuilder,
+    file: &T,
+    options: &ResponseOptions,
+    encoding: Encoding,
+    compress: impl FnOnce(&str, &[u8]) -> Result<Vec<u8>, CompressionError>,
+) -> HttpResponse {
+    match compress(&file.cache_key(), file.data().as_ref()) {
+        Ok(compressed) => {
+            resp.append_header(("Content-Encoding", encoding.name()));
+            resp.body(compressed)
+        }
+        Err(error) => {
+            if let Some(hook) = &options.compression_error_hook {
+                hook.report(&error);
+            }
+            resp.body(file.data())
+        }
+    }
+}
+
 impl<T: EmbedRespondable> Responder for EmbedResponse<T> {
     type Body = BoxBody;
 
     fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let options = self.options.with_config(embed_config(req));
         match self.file {
             Some(file) => {
                 // This responder can't respond to anything other than GET and HEAD requests.
                 if req.method() != Method::GET && req.method() != Method::HEAD {
-                    return HttpResponse::NotImplemented().finish();
+                    return HttpResponse::MethodNotAllowed()
+                        .insert_header((header::ALLOW, "GET, HEAD"))
+                        .finish();
+                }
+
+                // The client's cached copy is for a normal response, so a
+                // response with a status always sends the file.
+                if let Some(status) = self.status {
+                    return send_with_status(req, &file, &options, status);
                 }
 
                 // For the ETag we are using the sha256 hash of the file, encoded with
-                // base64. We surround it with quotes as per the spec.
-                let e = file.etag();
-                let etag = e.as_ref();
+                // base64. We surround it with quotes as per the spec. The header
+                // value is used when the file has one, since files can reuse it
+                // across responses while `etag()` may build a new string.
+                let etag_header = file.etag_header();
+                let e: Cow<'_, str> = match etag_header.as_ref().and_then(|v| v.to_str().ok()) {
+                    Some(etag) => Cow::Borrowed(etag),
+                    None => Cow::Owned(file.etag().as_ref().to_string()),
+                };
+                // If-None-Match uses the weak comparison, so a weak ETag
+                // matches the same tag without the `W/` prefix.
+                let etag = e.strip_prefix("W/").unwrap_or(&e);
 
                 let last_modified_timestamp = file.last_modified_timestamp();
 
@@ -196,7 +510,7 @@ impl<T: EmbedRespondable> Responder for EmbedResponse<T> {
                     if req_etags.contains(&etag) {
                         return HttpResponse::NotModified().finish();
                     } else {
-                        return send_response(req, &file, self.compress);
+                        return send_response(req, &file, &options, etag_header.clone());
                     }
                 }
                 // If there was no `If-None-Match` condition, check for
@@ -212,7 +526,7 @@ impl<T: EmbedRespondable> Responder for EmbedResponse<T> {
                     {
                         // It's been modified since then
                         if last_modified_timestamp > if_unmodified_since.timestamp() {
-                            return send_response(req, &file, self.compress);
+                            return send_response(req, &file, &options, etag_header.clone());
                         } else {
                             return HttpResponse::NotModified().finish();
                         }
@@ -220,9 +534,12 @@ impl<T: EmbedRespondable> Responder for EmbedResponse<T> {
                 }
                 // If there was no `If-Unmodified-Since` header either, that
                 // means the client does not have this file cached.
-                send_response(req, &file, self.compress)
+                send_response(req, &file, &options, etag_header.clone())
             }
-            None => HttpResponse::NotFound().finish(),
+            None => match &self.not_found_page {
+                Some(page) => send_error_page(req, page, &options, StatusCode::NOT_FOUND),
+                None => HttpResponse::NotFound().finish(),
+            },
         }
     }
 }
@@ -231,7 +548,108 @@ impl<T: EmbedRespondable> EmbedResponse<T> {
     /// Set the compression option to use for this response. Please see the
     /// Compress type for allowed options.
     pub fn use_compression(mut self, option: Compress) -> Self {
-        self.compress = option;
+        self.options.compress = Some(option);
+        self
+    }
+
+    /// Set rules for compressing some files differently, overriding the
+    /// compression option for the files they match. Please see the
+    /// CompressionRules type for details.
+    pub fn use_compression_rules(mut self, rules: impl Into<Arc<CompressionRules>>) -> Self {
+        self.options.compression_rules = Some(rules.into());
+        self
+    }
+
+    /// Call this function when compressing the response fails, for example
+    /// to log the error. The response is sent uncompressed either way.
+    pub fn on_compression_error(
+        mut self,
+        hook: impl Fn(&CompressionError) + Send + Sync + 'static,
+    ) -> Self {
+        self.options.compression_error_hook = Some(CompressionErrorHook::new(hook));
+        self
+    }
+
+    /// Send this page if the file doesn't exist, instead of an empty
+    /// `404 Not Found` response.
+    ///
+    /// ```ignore
+    /// Embed::get(&path)
+    ///     .into_response()
+    ///     .not_found_with(Embed::get("404.html"))
+    /// ```
+    ///
+    /// The page is sent with its own `Content-Type` and is compressed like
+    /// any other file, but is never cached.
+    pub fn not_found_with(mut self, page: impl IntoResponse<T>) -> Self {
+        self.not_found_page = page.into_response().file;
+        self
+    }
+
+    /// Send the file with this status, instead of `200 OK`.
+    ///
+    /// ```ignore
+    /// Embed::get("maintenance.html")
+    ///     .into_response()
+    ///     .with_status(StatusCode::SERVICE_UNAVAILABLE)
+    /// ```
+    ///
+    /// The file is always sent, even if the client has it cached already.
+    /// For statuses other than `2xx`, the response is never cached and has
+    /// no ETag or last modified date, like an error page. If the file
+    /// doesn't exist, the response is still a `404 Not Found`.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Set the caching option to use for this response. Please see the
+    /// CachePolicy type for allowed options.
+    pub fn use_cache_policy(mut self, option: CachePolicy) -> Self {
+        self.options.cache = Some(option);
+        self
+    }
+
+    /// Set what to send as the `Content-Type` if the mime type of the file is
+    /// not known. Please see the MimeFallback type for allowed options.
+    pub fn use_mime_fallback(mut self, option: MimeFallback) -> Self {
+        self.options.mime_fallback = Some(option);
+        self
+    }
+
+    /// Set the mime type overrides and charset to use for this response.
+    /// Please see the MimeOverrides type for details.
+    pub fn use_mime_overrides(mut self, overrides: impl Into<Arc<MimeOverrides>>) -> Self {
+        self.options.mime_overrides = Some(overrides.into());
+        self
+    }
+
+    /// Make the browser download the file, saving it with this filename.
+    ///
+    /// This sends a `Content-Disposition: attachment` header. The header is
+    /// only added to `200 OK` and `206 Partial Content` responses, not to
+    /// `304 Not Modified`, error responses or other statuses set with
+    /// `with_status`.
+    pub fn as_attachment(mut self, filename: impl AsRef<str>) -> Self {
+        self.options.disposition = Some(content_disposition(
+            DispositionType::Attachment,
+            filename.as_ref(),
+        ));
+        self
+    }
+
+    /// Have the browser display the file, using this filename if the user
+    /// decides to save it.
+    ///
+    /// This sends a `Content-Disposition: inline` header. The header is only
+    /// added to `200 OK` and `206 Partial Content` responses, not to
+    /// `304 Not Modified`, error responses or other statuses set with
+    /// `with_status`.
+    pub fn inline(mut self, filename: impl AsRef<str>) -> Self {
+        self.options.disposition = Some(content_disposition(
+            DispositionType::Inline,
+            filename.as_ref(),
+        ));
         self
     }
 }
@@ -241,3 +659,90 @@ pub trait IntoResponse<T: EmbedRespondable> {
     /// A specialized version of `Into::into`.
     fn into_response(self) -> EmbedResponse<T>;
 }
+
+impl<T: EmbedRespondable> From<T> for EmbedResponse<T> {
+    fn from(file: T) -> Self {
+        EmbedResponse::new(Some(file))
+    }
+}
+
+impl<T: EmbedRespondable> From<Option<T>> for EmbedResponse<T> {
+    fn from(file: Option<T>) -> Self {
+        EmbedResponse::new(file)
+    }
+}
+
+impl<T: EmbedRespondable> IntoResponse<T> for T {
+    fn into_response(self) -> EmbedResponse<T> {
+        self.into()
+    }
+}
+
+impl<T: EmbedRespondable> IntoResponse<T> for Option<T> {
+    fn into_response(self) -> EmbedResponse<T> {
+        self.into()
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use actix_web::{body::MessageBody, HttpResponse};
+
+    use crate::{
+        compress::{CompressionError, CompressionErrorHook},
+        compression_rules::Encoding,
+        embed::{send_compressed, ResponseOptions},
+        memory::MemoryFile,
+    };
+
+    #[test]
+    fn failed_compression_sends_the_file_uncompressed() {
+        let errors = Arc::new(AtomicUsize::new(0));
+        let counter = errors.clone();
+        let options = ResponseOptions {
+            compression_error_hook: Some(CompressionErrorHook::new(move |error| {
+                assert_eq!(error.encoding(), Encoding::Brotli);
+                counter.fetch_add(1, Ordering::Relaxed);
+            })),
+            ..Default::default()
+        };
+        let resp = send_compressed(
+            HttpResponse::Ok(),
+            &MemoryFile::new("hello"),
+            &options,
+            Encoding::Brotli,
+            |_, _| {
+                Err(CompressionError::new(
+                    Encoding::Brotli,
+                    std::io::Error::other("encoder failed"),
+                ))
+            },
+        );
+        assert!(resp.headers().get("Content-Encoding").is_none());
+        assert_eq!(resp.into_body().try_into_bytes().unwrap(), "hello");
+        assert_eq!(errors.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn compressed_responses_have_an_encoding() {
+        let options = ResponseOptions {
+            compression_error_hook: Some(CompressionErrorHook::new(|error| {
+                panic!("unexpected compression error: {error}")
+            })),
+            ..Default::default()
+        };
+        let resp = send_compressed(
+            HttpResponse::Ok(),
+            &MemoryFile::new("hello"),
+            &options,
+            Encoding::Brotli,
+            |_, data| Ok(data.to_vec()),
+        );
+        assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "br");
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use actix_web::http::StatusCode;
+
+/// Pages from the embed to send for error responses, instead of an empty
+/// body.
+///
+/// Each page is sent with the status of the error, like `404 Not Found`, and
+/// with its own `Content-Type`. Pages are compressed like any other file, but
+/// they are never cached.
+///
+/// `EmbedService` sends `400 Bad Request` for invalid paths, `404 Not Found`
+/// for missing files and `405 Method Not Allowed` for methods other than
+/// `GET` and `HEAD`. Pages for other statuses are only used for the empty
+/// responses of `_redirects` rules with that status, like a `410` rule whose
+/// target doesn't exist. Maintenance mode has its own page.
+///
+/// ```
+/// use actix_web::http::StatusCode;
+/// use actix_web_rust_embed_responder::ErrorPages;
+///
+/// let pages = ErrorPages::new()
+///     .not_found("404.html")
+///     .page(StatusCode::METHOD_NOT_ALLOWED, "errors/405.html");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ErrorPages {
+    pages: HashMap<StatusCode, String>,
+}
+
+impl ErrorPages {
+    /// No error pages, all errors have an empty body.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The page to send when a file doesn't exist.
+    pub fn not_found(self, path: impl Into<String>) -> Self {
+        self.page(StatusCode::NOT_FOUND, path)
+    }
+
+    /// The page to send for responses with this status. The path is
+    /// relative to the root of the embed, like `errors/405.html`.
+    pub fn page(mut self, status: StatusCode, path: impl Into<String>) -> Self {
+        self.pages
+            .insert(status, path.into().trim_start_matches('/').to_string());
+        self
+    }
+
+    /// The path of the page for this status, if there is one.
+    pub(crate) fn path_for(&self, status: StatusCode) -> Option<&str> {
+        self.pages.get(&status).map(String::as_str)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use actix_web::http::StatusCode;
+
+    use crate::error_pages::ErrorPages;
+
+    #[test]
+    fn pages_are_found_by_status() {
+        let pages = ErrorPages::new()
+            .not_found("/404.html")
+            .page(StatusCode::METHOD_NOT_ALLOWED, "errors/405.html");
+        assert_eq!(pages.path_for(StatusCode::NOT_FOUND), Some("404.html"));
+        assert_eq!(
+            pages.path_for(StatusCode::METHOD_NOT_ALLOWED),
+            Some("errors/405.html")
+        );
+        assert_eq!(pages.path_for(StatusCode::BAD_REQUEST), None);
+    }
+}
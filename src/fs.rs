@@ -0,0 +1,352 @@
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    fs, io,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, OnceLock, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use actix_web::{http::header::HeaderValue, web::Bytes};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    bounded::BoundedCache,
+    embed::EmbedRespondable,
+    headers::{FileHeaders, HeaderStr},
+    source::EmbedSource,
+};
+
+/// The headers of a file, and the modification time and size it had when it
+/// was hashed.
+#[derive(Clone, Debug)]
+struct HashEntry {
+    modified: SystemTime,
+    len: u64,
+    headers: Arc<FileHeaders>,
+}
+
+type Hashes = Arc<RwLock<BoundedCache<PathBuf, HashEntry>>>;
+
+/// How many files have their hash cached. Files that were deleted or
+/// renamed are dropped from the cache once it is full.
+const MAX_CACHED_HASHES: usize = 16 * 1024;
+
+/// Serves the files in a directory at runtime, like assets uploaded by users
+/// or a frontend that is built separately from the server.
+///
+/// The files get the same ETags as `rust-embed` files with the same
+/// contents, and are served with the same caching and compression. With the
+/// `mime_guess` feature, their mime types are guessed from their extensions.
+/// The hash of each file is cached, and only computed again when the
+/// modification time or the size of the file changes. Files are only read
+/// when the data is needed, so `304 Not Modified` and `HEAD` responses for a
+/// file that was hashed before don't read it again. If a file can't be read
+/// anymore when its data is needed, the response is a `404 Not Found` or a
+/// `500 Internal Server Error`.
+///
+/// Paths that would leave the directory are rejected, including symlinks
+/// that point outside of it.
+///
+/// With the `mmap` feature, `use_mmap` maps the files into memory instead
+/// of reading them. If a mapped file is truncated while it is being sent,
+/// the process is killed with `SIGBUS`, so only use it for files that are
+/// replaced rather than written to in place.
+///
+/// ```ignore
+/// let source = FsSource::new("./public")?;
+/// App::new().service(EmbedService::new("/", source))
+/// ```
+#[derive(Clone, Debug)]
+pub struct FsSource {
+    root: PathBuf,
+    #[cfg(feature = "mmap")]
+    mmap: bool,
+    hashes: Hashes,
+}
+
+impl FsSource {
+    /// Serve the files in this directory. Fails if the directory doesn't
+    /// exist.
+    pub fn new(root: impl AsRef<Path>) -> io::Result<Self> {
+        let root = root.as_ref().canonicalize()?;
+        if !root.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not a directory", root.display()),
+            ));
+        }
+        Ok(FsSource {
+            root,
+            #[cfg(feature = "mmap")]
+            mmap: false,
+            hashes: Arc::new(RwLock::new(BoundedCache::new(MAX_CACHED_HASHES))),
+        })
+    }
+
+    /// Memory map the files instead of reading them into memory for every
+    /// request. This is faster for large files, but the files must not be
+    /// truncated while they are being served, or the process is killed with
+    /// `SIGBUS`.
+    #[cfg(feature = "mmap")]
+    pub fn use_mmap(mut self) -> Self {
+        self.mmap = true;
+        self
+    }
+
+    /// The file at this path, if it is inside the directory.
+    fn resolve(&self, path: &str) -> Option<PathBuf> {
+        let path = Path::new(path.trim_start_matches('/'));
+        // Only plain names, no `..`, roots or prefixes.
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return None;
+        }
+        // Canonicalizing follows symlinks, so a link to somewhere outside of
+        // the directory ends up outside of the root.
+        let resolved = self.root.join(path).canonicalize().ok()?;
+        (resolved.starts_with(&self.root) && resolved.is_file()).then_some(resolved)
+    }
+
+    /// The file at this path. The file is not read yet.
+    pub fn get(&self, path: &str) -> Option<FsFile> {
+        let resolved = self.resolve(path)?;
+        let metadata = fs::metadata(&resolved).ok()?;
+        Some(FsFile {
+            path: path.trim_start_matches('/').into(),
+            resolved,
+            modified: metadata.modified().unwrap_or(UNIX_EPOCH),
+            len: metadata.len(),
+            #[cfg(feature = "mmap")]
+            mmap: self.mmap,
+            hashes: self.hashes.clone(),
+            data: OnceLock::new(),
+            headers: OnceLock::new(),
+        })
+    }
+
+    /// Adds the paths of the files in `dir` to `paths`. `parents` has the
+    /// directories above this one, so symlinks can't make this loop forever.
+    fn walk(
+        &self,
+        dir: &Path,
+        prefix: &str,
+        parents: &mut HashSet<PathBuf>,
+        paths: &mut Vec<String>,
+    ) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        if !parents.insert(dir.to_path_buf()) {
+            return;
+        }
+        for entry in entries.flatten() {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let path = format!("{prefix}{name}");
+            let Ok(resolved) = entry.path().canonicalize() else {
+                continue;
+            };
+            if !resolved.starts_with(&self.root) {
+                continue;
+            }
+            if resolved.is_dir() {
+                self.walk(&resolved, &format!("{path}/"), parents, paths);
+            } else if resolved.is_file() {
+                paths.push(path);
+            }
+        }
+        parents.remove(dir);
+    }
+}
+
+impl EmbedSource for FsSource {
+    type File = FsFile;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        FsSource::get(self, path)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        let mut paths = Vec::new();
+        self.walk(&self.root, "", &mut HashSet::new(), &mut paths);
+        paths.sort();
+        Box::new(paths.into_iter().map(Cow::Owned))
+    }
+}
+
+/// A file from a `FsSource`.
+///
+/// The file is read the first time its data is needed, and hashed the first
+/// time its ETag is needed if the hash isn't cached yet.
+pub struct FsFile {
+    path: Arc<str>,
+    resolved: PathBuf,
+    modified: SystemTime,
+    len: u64,
+    #[cfg(feature = "mmap")]
+    mmap: bool,
+    hashes: Hashes,
+    data: OnceLock<Result<Bytes, io::ErrorKind>>,
+    headers: OnceLock<Option<Arc<FileHeaders>>>,
+}
+
+impl FsFile {
+    fn read(&self) -> io::Result<Bytes> {
+        #[cfg(feature = "mmap")]
+        if self.mmap {
+            let file = fs::File::open(&self.resolved)?;
+            // Safety: the file could be changed by another process while it
+            // is mapped. That's documented on `FsSource::use_mmap`.
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+            return Ok(Bytes::from_owner(map));
+        }
+        fs::read(&self.resolved).map(Bytes::from)
+    }
+
+    /// The data of the file, read only once. Reading fails if the file
+    /// can't be read anymore, like if it was deleted after `get`.
+    fn read_once(&self) -> Result<&Bytes, io::ErrorKind> {
+        self.data
+            .get_or_init(|| self.read().map_err(|error| error.kind()))
+            .as_ref()
+            .map_err(|kind| *kind)
+    }
+
+    /// The headers of the file, from the cache if the file hasn't changed.
+    /// `None` if the file had to be hashed but couldn't be read.
+    fn headers(&self) -> Option<&FileHeaders> {
+        self.headers
+            .get_or_init(|| {
+                let cached = self
+                    .hashes
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get(&self.resolved)
+                    .cloned();
+                if let Some(entry) = cached {
+                    if entry.modified == self.modified && entry.len == self.len {
+                        return Some(entry.headers);
+                    }
+                }
+
+                let data = self.read_once().ok()?;
+                let headers = Arc::new(FileHeaders::new(
+                    Sha256::digest(data).into(),
+                    self.last_modified_timestamp(),
+                ));
+                // The file could have been written to between `get` and reading
+                // it, and then the hash doesn't belong to this modification time.
+                // It is only cached if the file is still the one `get` saw.
+                let unchanged = fs::metadata(&self.resolved).is_ok_and(|metadata| {
+                    metadata.modified().unwrap_or(UNIX_EPOCH) == self.modified
+                        && metadata.len() == self.len
+                        && data.len() as u64 == self.len
+                });
+                if unchanged {
+                    self.hashes
+                        .write()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(
+                            self.resolved.clone(),
+                            HashEntry {
+                                modified: self.modified,
+                                len: self.len,
+                                headers: headers.clone(),
+                            },
+                            1,
+                        );
+                }
+                Some(headers)
+            })
+            .as_deref()
+    }
+}
+
+impl EmbedRespondable for FsFile {
+    type Data = Bytes;
+    type DataGzip = Bytes;
+    type DataBr = Bytes;
+    type DataZstd = Bytes;
+    type MimeType = &'static str;
+    type ETag = HeaderStr;
+    type LastModified = HeaderStr;
+
+    fn data(&self) -> Self::Data {
+        // `load_data` fails the response before this if the file can't be
+        // read, so the empty data is never sent.
+        self.read_once().cloned().unwrap_or_default()
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        None
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        None
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        None
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        self.modified
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(|duration| duration.as_secs().try_into().ok())
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        self.headers()?.last_modified.clone()
+    }
+
+    fn etag(&self) -> Self::ETag {
+        match self.headers() {
+            Some(headers) => headers.etag.clone(),
+            // The file can't be read, so there is nothing to identify. This
+            // is never sent, since `etag_header` is `None`.
+            None => HeaderStr::new("\"\"").expect("ETag is not a valid header value"),
+        }
+    }
+
+    #[cfg(not(feature = "mime_guess"))]
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        // Without `mime_guess` the browser or the mime fallback has to guess.
+        None
+    }
+
+    #[cfg(feature = "mime_guess")]
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        mime_guess::from_path(self.path.as_ref()).first_raw()
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(&self.path))
+    }
+
+    fn size(&self) -> usize {
+        match self.data.get() {
+            Some(Ok(data)) => data.len(),
+            _ => self.len.try_into().unwrap_or(usize::MAX),
+        }
+    }
+
+    fn load_data(&self) -> io::Result<()> {
+        self.read_once().map(|_| ()).map_err(io::Error::from)
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        Some(self.headers()?.etag.header_value().clone())
+    }
+
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        self.headers()?
+            .last_modified
+            .as_ref()
+            .map(|value| value.header_value().clone())
+    }
+}
@@ -0,0 +1,138 @@
+use regex::Regex;
+
+/// A glob pattern for the paths in an embed, like `*.map` or `drafts/**`.
+///
+/// - `*` matches anything except a `/`, and `?` matches any single
+///   character except a `/`.
+/// - `**` matches anything, including `/`. `docs/**/*.md` matches
+///   `docs/a.md` and `docs/a/b.md`.
+/// - A pattern without a `/` matches any file or directory with that name,
+///   wherever it is. `.*` matches `.DS_Store` as well as `assets/.git/config`.
+/// - A pattern with a `/` matches from the root of the embed. A leading `/`
+///   can be used to anchor a pattern without any other `/`, like `/README.md`.
+///   Matching a directory also matches everything inside it.
+#[derive(Clone, Debug)]
+pub(crate) struct Glob {
+    regex: Regex,
+    /// Whether the pattern matches the whole path, or any of its segments.
+    anchored: bool,
+}
+
+impl Glob {
+    pub(crate) fn new(pattern: &str) -> Self {
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/').trim_end_matches('/');
+        let mut regex = String::from("^");
+        let mut rest = pattern;
+        while let Some(c) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("**/") {
+                regex.push_str("(?:.*/)?");
+                rest = after;
+            } else if rest == "**" {
+                regex.push_str(".*");
+                rest = "";
+            } else if let Some(after) = rest.strip_prefix("/**") {
+                if after.is_empty() {
+                    regex.push_str("(?:/.*)?");
+                    rest = after;
+                } else {
+                    regex.push('/');
+                    rest = &rest[1..];
+                }
+            } else if let Some(after) = rest.strip_prefix("**") {
+                regex.push_str(".*");
+                rest = after;
+            } else {
+                match c {
+                    '*' => regex.push_str("[^/]*"),
+                    '?' => regex.push_str("[^/]"),
+                    c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+                }
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        if anchored {
+            // Matching a directory matches everything in it
+            regex.push_str("(?:/.*)?");
+        }
+        regex.push('$');
+        Glob {
+            // Everything that isn't a wildcard is escaped, so this is always valid
+            regex: Regex::new(&regex).expect("Glob is not a valid regex"),
+            anchored,
+        }
+    }
+
+    /// Whether the path matches the pattern. The path should not start with
+    /// a `/`.
+    pub(crate) fn is_match(&self, path: &str) -> bool {
+        let path = path.trim_end_matches('/');
+        if self.anchored {
+            self.regex.is_match(path)
+        } else {
+            path.split('/').any(|segment| self.regex.is_match(segment))
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::glob::Glob;
+
+    #[test]
+    fn star_matches_names() {
+        let glob = Glob::new("*.map");
+        assert!(glob.is_match("app.js.map"));
+        assert!(glob.is_match("assets/js/app.js.map"));
+        assert!(!glob.is_match("app.js"));
+        assert!(!glob.is_match("map"));
+    }
+
+    #[test]
+    fn dotfiles_match_at_any_level() {
+        let glob = Glob::new(".*");
+        assert!(glob.is_match(".DS_Store"));
+        assert!(glob.is_match("assets/.DS_Store"));
+        assert!(glob.is_match(".git/config"));
+        assert!(!glob.is_match("index.html"));
+    }
+
+    #[test]
+    fn anchored_patterns_match_from_the_root() {
+        let glob = Glob::new("/README.md");
+        assert!(glob.is_match("README.md"));
+        assert!(!glob.is_match("docs/README.md"));
+
+        let glob = Glob::new("internal/");
+        assert!(glob.is_match("internal"));
+        assert!(glob.is_match("internal/secret.txt"));
+        assert!(!glob.is_match("public/internal/secret.txt"));
+    }
+
+    #[test]
+    fn double_star_matches_directories() {
+        let glob = Glob::new("docs/**/*.md");
+        assert!(glob.is_match("docs/a.md"));
+        assert!(glob.is_match("docs/a/b/c.md"));
+        assert!(!glob.is_match("a.md"));
+
+        let glob = Glob::new("drafts/**");
+        assert!(glob.is_match("drafts"));
+        assert!(glob.is_match("drafts/a/b.html"));
+        assert!(!glob.is_match("draftsman.html"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        let glob = Glob::new("v?.txt");
+        assert!(glob.is_match("v1.txt"));
+        assert!(!glob.is_match("v10.txt"));
+    }
+
+    #[test]
+    fn regex_characters_are_escaped() {
+        let glob = Glob::new("a+(b).[c]");
+        assert!(glob.is_match("a+(b).[c]"));
+        assert!(!glob.is_match("aa(b)x[c]"));
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+
+use actix_web::http::header::HeaderValue;
+use base64::{engine::general_purpose::STANDARD_NO_PAD as Base64Encoder, Engine};
+use chrono::TimeZone;
+
+/// A header value that has been validated once and can be reused for every
+/// response.
+///
+/// This is used as the `ETag` and `LastModified` types for files where the
+/// header values have to be computed at runtime, and can be used the same way
+/// by your own `EmbedRespondable` files. Cloning it is cheap, and it can be
+/// used as a string through `AsRef<str>`.
+#[derive(Clone, Debug)]
+pub struct HeaderStr(HeaderValue);
+
+impl HeaderStr {
+    /// Validates the value, returning `None` if it can't be used in a header.
+    /// Only visible ASCII characters, spaces and tabs are accepted, so values
+    /// with newlines or other control characters are rejected.
+    pub fn new(value: impl Into<String>) -> Option<Self> {
+        HeaderValue::try_from(value.into())
+            .ok()
+            .filter(|value| value.to_str().is_ok())
+            .map(HeaderStr)
+    }
+
+    /// The header value, ready to be added to a response.
+    pub fn header_value(&self) -> &HeaderValue {
+        &self.0
+    }
+}
+
+impl AsRef<str> for HeaderStr {
+    fn as_ref(&self) -> &str {
+        // `new` only accepts visible ASCII, so this can't actually fail.
+        self.0.to_str().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for HeaderStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl From<HeaderStr> for HeaderValue {
+    fn from(value: HeaderStr) -> Self {
+        value.0
+    }
+}
+
+/// The header values of a single file that are worth computing only once.
+#[derive(Clone, Debug)]
+pub(crate) struct FileHeaders {
+    pub(crate) etag: HeaderStr,
+    pub(crate) last_modified: Option<HeaderStr>,
+}
+
+impl FileHeaders {
+    /// The header values for a file with this sha256 hash and last modified
+    /// time. The ETag is the base64 encoded hash, the same for every kind of
+    /// file.
+    pub(crate) fn new(hash: [u8; 32], last_modified_timestamp: Option<i64>) -> Self {
+        FileHeaders {
+            etag: Self::format_etag(&hash),
+            last_modified: last_modified_timestamp.and_then(Self::format_last_modified),
+        }
+    }
+
+    /// The ETag for a sha256 hash, base64 encoded and in quotes.
+    pub(crate) fn format_etag(hash: &[u8]) -> HeaderStr {
+        HeaderStr::new(format!("\"{}\"", Base64Encoder.encode(hash)))
+            // base64 and quotes are always valid in a header.
+            .expect("ETag is not a valid header value")
+    }
+
+    /// The rfc2822 date for a UNIX timestamp, if the timestamp is in range.
+    pub(crate) fn format_last_modified(timestamp: i64) -> Option<HeaderStr> {
+        chrono::Utc
+            .timestamp_opt(timestamp, 0)
+            .single()
+            .and_then(|date| HeaderStr::new(date.to_rfc2822()))
+    }
+}
+
+/// Files are identified by their hash and their last modified time. Two files
+/// with the same contents could have different modification times, so the
+/// hash alone is not enough.
+///
+/// Only the embedded files use this cache. Files that can change at runtime
+/// keep their headers themselves.
+#[cfg(any(
+    feature = "support-rust-embed",
+    feature = "archive",
+    feature = "support-include-dir"
+))]
+pub(crate) type FileHeadersKey = ([u8; 32], Option<i64>);
+
+/// How many files have their headers cached.
+#[cfg(any(
+    feature = "support-rust-embed",
+    feature = "archive",
+    feature = "support-include-dir"
+))]
+pub(crate) const MAX_CACHED_HEADERS: usize = 16 * 1024;
+
+// Putting the headers into cache could potentially fail. That's okay if it
+// does happen, we'll just compute the headers again next time.
+/// Gets the header values for a file, computing them only the first time the
+/// file is seen.
+#[cfg(any(
+    feature = "support-rust-embed",
+    feature = "archive",
+    feature = "support-include-dir"
+))]
+pub(crate) fn cached_file_headers(
+    key: FileHeadersKey,
+    compute: impl FnOnce() -> FileHeaders,
+) -> std::sync::Arc<FileHeaders> {
+    use crate::bounded::BoundedCache;
+    use lazy_static::lazy_static;
+    use std::sync::{Arc, RwLock};
+
+    lazy_static! {
+        static ref CACHED_HEADERS: RwLock<BoundedCache<FileHeadersKey, Arc<FileHeaders>>> =
+            RwLock::new(BoundedCache::new(MAX_CACHED_HEADERS));
+    }
+
+    if let Some(headers) = CACHED_HEADERS
+        .read()
+        .ok()
+        .and_then(|cached| cached.get(&key).cloned())
+    {
+        return headers;
+    }
+
+    let headers = Arc::new(compute());
+    if let Ok(mut cached) = CACHED_HEADERS.write() {
+        cached.insert(key, headers.clone(), 1);
+    }
+    headers
+}
+
+/// Gets the header values for a file from its sha256 hash and last modified
+/// time, computing them only the first time the file is seen.
+#[cfg(any(
+    feature = "support-rust-embed",
+    feature = "archive",
+    feature = "support-include-dir"
+))]
+pub(crate) fn hashed_file_headers(
+    hash: [u8; 32],
+    last_modified_timestamp: Option<i64>,
+) -> std::sync::Arc<FileHeaders> {
+    cached_file_headers((hash, last_modified_timestamp), || {
+        FileHeaders::new(hash, last_modified_timestamp)
+    })
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::headers::{FileHeaders, HeaderStr};
+    use std::sync::Arc;
+
+    #[test]
+    fn header_str_roundtrip() {
+        let value = HeaderStr::new("\"abc\"".to_string()).unwrap();
+        assert_eq!(value.as_ref(), "\"abc\"");
+        assert_eq!(value.header_value(), "\"abc\"");
+    }
+
+    #[test]
+    fn header_str_rejects_invalid_values() {
+        assert!(HeaderStr::new("foo\nbar").is_none());
+        // Valid in a header, but not as a string
+        assert!(HeaderStr::new("caf\u{e9}").is_none());
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "support-rust-embed",
+        feature = "archive",
+        feature = "support-include-dir"
+    ))]
+    fn file_headers_are_cached() {
+        use crate::headers::cached_file_headers;
+
+        let compute = || FileHeaders {
+            etag: HeaderStr::new("\"cached\"".to_string()).unwrap(),
+            last_modified: None,
+        };
+        let first = cached_file_headers(([1; 32], Some(10)), compute);
+        let second = cached_file_headers(([1; 32], Some(10)), || {
+            panic!("Headers should not be computed twice")
+        });
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // A different modification time is a different file
+        let third = cached_file_headers(([1; 32], Some(11)), compute);
+        assert!(!Arc::ptr_eq(&first, &third));
+    }
+}
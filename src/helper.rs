@@ -1,5 +1,19 @@
 use crate::parse::parse_accept_encoding_value;
 use actix_web::HttpRequest;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// The characters that have to be escaped in the path of a URL.
+const URL_PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
 
 pub(crate) fn accepts_encoding(req: &HttpRequest, encoding: &str) -> bool {
     req.headers()
@@ -8,3 +22,8 @@ pub(crate) fn accepts_encoding(req: &HttpRequest, encoding: &str) -> bool {
         .map(|encodings| encodings.contains(&encoding))
         .unwrap_or(false)
 }
+
+/// Escape a path so it can be used in a URL, keeping the `/` separators.
+pub(crate) fn encode_url_path(path: &str) -> String {
+    utf8_percent_encode(path, URL_PATH).to_string()
+}
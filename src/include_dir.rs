@@ -0,0 +1,174 @@
+use actix_web::http::header::HeaderValue;
+use include_dir::{Dir, File};
+use lazy_static::lazy_static;
+use sha2::{Digest, Sha256};
+use std::{
+    borrow::Cow,
+    ops::Deref,
+    path::{Component, Path},
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    bounded::BoundedCache,
+    embed::{EmbedRespondable, EmbedResponse},
+    headers::{FileHeaders, HeaderStr, MAX_CACHED_HEADERS},
+    source::EmbedSource,
+};
+
+/// The contents of a file are static, so their address and length identify it.
+type FileKey = (usize, usize);
+
+/// include_dir doesn't hash the files, so we hash each file the first time it
+/// is seen and keep its headers.
+fn file_headers(file: &File<'static>) -> Arc<FileHeaders> {
+    lazy_static! {
+        static ref CACHED_HEADERS: RwLock<BoundedCache<FileKey, Arc<FileHeaders>>> =
+            RwLock::new(BoundedCache::new(MAX_CACHED_HEADERS));
+    }
+
+    let contents = file.contents();
+    let key = (contents.as_ptr() as usize, contents.len());
+    if let Some(headers) = CACHED_HEADERS
+        .read()
+        .ok()
+        .and_then(|cached| cached.get(&key).cloned())
+    {
+        return headers;
+    }
+
+    // include_dir only has modification times with its `metadata` feature,
+    // which we can't check for, so no Last-Modified header is sent.
+    let headers = Arc::new(FileHeaders::new(Sha256::digest(contents).into(), None));
+    // Putting the headers into cache could potentially fail. That's okay if
+    // it does happen, we'll just hash the file again next time.
+    if let Ok(mut cached) = CACHED_HEADERS.write() {
+        cached.insert(key, headers.clone(), 1);
+    }
+    headers
+}
+
+/// The path of the file with `/` separators, like `assets/style.css`.
+fn file_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl EmbedRespondable for &'static File<'static> {
+    type Data = &'static [u8];
+    type DataGzip = Vec<u8>;
+    type DataBr = Vec<u8>;
+    type DataZstd = Vec<u8>;
+    type ETag = HeaderStr;
+    type LastModified = HeaderStr;
+    type MimeType = &'static str;
+
+    fn data(&self) -> Self::Data {
+        // `contents` borrows from the file, so it has to be called on the
+        // static reference to get static data.
+        let file: &'static File<'static> = self;
+        file.contents()
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        None
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        None
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        None
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        None
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        None
+    }
+
+    fn etag(&self) -> Self::ETag {
+        file_headers(self).etag.clone()
+    }
+
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        mime_guess::from_path(self.path()).first_raw()
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.path().to_str().map(Cow::Borrowed)
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        Some(file_headers(self).etag.header_value().clone())
+    }
+}
+
+impl Deref for EmbedResponse<&'static File<'static>> {
+    type Target = Option<&'static File<'static>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.file
+    }
+}
+
+/// The files of an `include_dir!` directory, as an `EmbedSource`.
+///
+/// ```ignore
+/// use actix_web_rust_embed_responder::{EmbedSource, IncludeDirSource};
+///
+/// static ASSETS: Dir = include_dir!("$CARGO_MANIFEST_DIR/assets");
+///
+/// let source = IncludeDirSource::new(&ASSETS);
+/// let file = source.get("index.html");
+/// ```
+///
+/// The paths are relative to the directory given to the source, even if it
+/// is a subdirectory of the one that was included.
+#[derive(Clone, Copy, Debug)]
+pub struct IncludeDirSource {
+    dir: &'static Dir<'static>,
+}
+
+impl IncludeDirSource {
+    /// Serve the files in this directory, usually a `static` made with
+    /// `include_dir!`.
+    pub fn new(dir: &'static Dir<'static>) -> Self {
+        IncludeDirSource { dir }
+    }
+}
+
+fn collect_files(dir: &'static Dir<'static>, root: &Path, paths: &mut Vec<String>) {
+    for file in dir.files() {
+        if let Ok(path) = file.path().strip_prefix(root) {
+            paths.push(file_path(path));
+        }
+    }
+    for dir in dir.dirs() {
+        collect_files(dir, root, paths);
+    }
+}
+
+impl EmbedSource for IncludeDirSource {
+    type File = &'static File<'static>;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        // include_dir paths are relative to the directory that was included.
+        self.dir
+            .get_file(self.dir.path().join(path.trim_start_matches('/')))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        let mut paths = Vec::new();
+        collect_files(self.dir, self.dir.path(), &mut paths);
+        Box::new(paths.into_iter().map(Cow::Owned))
+    }
+}
@@ -1,12 +1,61 @@
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "archive")]
+pub use archive::{ArchiveError, ArchiveFile, ArchiveSource};
+mod bounded;
+mod cache;
+pub use cache::CachePolicy;
+mod config;
+pub use config::EmbedConfig;
+mod disposition;
+mod dyn_embed;
+pub use dyn_embed::{DynEmbedFile, DynEmbedRespondable, DynEmbedResponse};
+mod error_pages;
+pub use error_pages::ErrorPages;
+mod fs;
+pub use fs::{FsFile, FsSource};
+mod glob;
 mod helper;
+mod listing;
+mod maintenance;
+pub use maintenance::MaintenanceMode;
+mod memory;
+pub use memory::{MemoryFile, MemoryFiles, MemoryMimeType};
+mod mime;
+pub use mime::MimeOverrides;
+mod netlify;
+pub use netlify::NetlifyConfigError;
+mod overlay;
+pub use overlay::{Overlay, OverlayFile, OverlayValue};
+mod overrides;
+pub use overrides::{OverrideValue, WithOverrides};
 mod parse;
+mod path;
+pub use path::{EmbedPath, EmbedPathConfig, EmbedPathError};
+mod sniff;
+pub use sniff::MimeFallback;
 
 mod compress;
 pub use compress::*;
+mod compression_rules;
+pub use compression_rules::{CompressionRule, CompressionRules, Encoding};
 mod embed;
+mod headers;
+pub use headers::HeaderStr;
 
 #[cfg(feature = "support-rust-embed")]
 mod rust_embed;
+#[cfg(feature = "support-rust-embed")]
+pub use crate::rust_embed::RustEmbedSource;
+#[cfg(feature = "mime-guess")]
+mod rust_embed_named;
+#[cfg(feature = "mime-guess")]
+pub use crate::rust_embed_named::*;
+
+#[cfg(feature = "support-include-dir")]
+mod include_dir;
+#[cfg(feature = "support-include-dir")]
+pub use crate::include_dir::IncludeDirSource;
 
 #[cfg(feature = "support-rust-embed-for-web")]
 mod rust_embed_for_web;
@@ -14,3 +63,13 @@ mod rust_embed_for_web;
 pub use crate::rust_embed_for_web::*;
 
 pub use embed::{EmbedRespondable, EmbedResponse, IntoResponse};
+mod rules;
+pub use rules::PathRules;
+mod service;
+pub use service::EmbedService;
+mod source;
+pub use source::EmbedSource;
+mod spa;
+pub use spa::SpaFallback;
+mod try_files;
+pub use try_files::TryFiles;
@@ -0,0 +1,318 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use actix_web::{http::header::HeaderValue, web::Bytes, HttpRequest};
+use chrono::TimeZone;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    embed::EmbedRespondable,
+    headers::{FileHeaders, HeaderStr},
+    helper::encode_url_path,
+    parse::parse_accept_value,
+    source::EmbedSource,
+};
+
+/// A file or directory inside a listed directory.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ListingEntry {
+    name: String,
+    is_directory: bool,
+    size: Option<usize>,
+    last_modified_timestamp: Option<i64>,
+}
+
+/// The contents of a directory in an embed.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Listing {
+    /// The URL path of the directory, ending with a `/`.
+    path: String,
+    entries: Vec<ListingEntry>,
+}
+
+/// List the directory at `directory`, which is either empty for the root of
+/// the source or ends with a `/`.
+///
+/// Returns `None` if there is no such directory. The root is always a
+/// directory, even if the source is empty or can't list its files.
+pub(crate) fn list_directory<S: EmbedSource>(
+    source: &S,
+    url_path: &str,
+    directory: &str,
+) -> Option<Listing> {
+    // Sorted by name, with the directories first
+    let mut entries: BTreeMap<(bool, String), ListingEntry> = BTreeMap::new();
+    for path in source.iter() {
+        let rest = match path.strip_prefix(directory) {
+            Some(rest) if !rest.is_empty() => rest,
+            _ => continue,
+        };
+        match rest.split_once('/') {
+            Some((name, _)) => {
+                entries
+                    .entry((false, name.to_string()))
+                    .or_insert_with(|| ListingEntry {
+                        name: name.to_string(),
+                        is_directory: true,
+                        size: None,
+                        last_modified_timestamp: None,
+                    });
+            }
+            None => {
+                let file = source.get(&path);
+                entries.insert(
+                    (true, rest.to_string()),
+                    ListingEntry {
+                        name: rest.to_string(),
+                        is_directory: false,
+                        size: file.as_ref().map(|file| file.size()),
+                        last_modified_timestamp: file
+                            .as_ref()
+                            .and_then(|file| file.last_modified_timestamp()),
+                    },
+                );
+            }
+        }
+    }
+    if entries.is_empty() && !directory.is_empty() {
+        return None;
+    }
+    Some(Listing {
+        path: url_path.to_string(),
+        entries: entries.into_values().collect(),
+    })
+}
+
+/// Whether the client asked for the listing as JSON instead of a page.
+pub(crate) fn wants_json(req: &HttpRequest) -> bool {
+    req.headers()
+        .get("Accept")
+        .and_then(parse_accept_value)
+        .map(|media_ranges| {
+            let accepts = |mime_type: &str| {
+                media_ranges
+                    .iter()
+                    .any(|range| range.eq_ignore_ascii_case(mime_type))
+            };
+            accepts("application/json") && !accepts("text/html")
+        })
+        .unwrap_or(false)
+}
+
+fn format_timestamp(timestamp: i64) -> Option<String> {
+    chrono::Utc
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|date| date.to_rfc3339())
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+impl Listing {
+    pub(crate) fn to_html(&self) -> String {
+        let path = escape_html(&self.path);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Index of {path}</title>\n</head>\n<body>\n<h1>Index of {path}</h1>\n<table>\n<thead><tr><th>Name</th><th>Size</th><th>Last modified</th></tr></thead>\n<tbody>\n"
+        );
+        if self.path != "/" {
+            html.push_str("<tr><td><a href=\"../\">../</a></td><td></td><td></td></tr>\n");
+        }
+        for entry in &self.entries {
+            let suffix = if entry.is_directory { "/" } else { "" };
+            let _ = writeln!(
+                html,
+                "<tr><td><a href=\"./{}{suffix}\">{}{suffix}</a></td><td>{}</td><td>{}</td></tr>",
+                escape_html(&encode_url_path(&entry.name)),
+                escape_html(&entry.name),
+                entry.size.map(|size| size.to_string()).unwrap_or_default(),
+                entry
+                    .last_modified_timestamp
+                    .and_then(format_timestamp)
+                    .unwrap_or_default(),
+            );
+        }
+        html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+        html
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut json = format!(
+                    "{{\"name\":{},\"type\":\"{}\"",
+                    escape_json(&entry.name),
+                    if entry.is_directory {
+                        "directory"
+                    } else {
+                        "file"
+                    }
+                );
+                if let Some(size) = entry.size {
+                    let _ = write!(json, ",\"size\":{size}");
+                }
+                if let Some(last_modified) =
+                    entry.last_modified_timestamp.and_then(format_timestamp)
+                {
+                    let _ = write!(json, ",\"last_modified\":{}", escape_json(&last_modified));
+                }
+                json.push('}');
+                json
+            })
+            .collect();
+        format!(
+            "{{\"path\":{},\"entries\":[{}]}}",
+            escape_json(&self.path),
+            entries.join(",")
+        )
+    }
+}
+
+/// A generated page, served like any other file so that it gets the same
+/// ETag, compression and conditional request handling.
+pub(crate) struct GeneratedPage {
+    data: Bytes,
+    mime_type: &'static str,
+    etag: HeaderStr,
+}
+
+impl GeneratedPage {
+    pub(crate) fn new(data: String, mime_type: &'static str) -> Self {
+        let etag = FileHeaders::format_etag(&Sha256::digest(data.as_bytes()));
+        GeneratedPage {
+            data: Bytes::from(data),
+            mime_type,
+            etag,
+        }
+    }
+}
+
+impl EmbedRespondable for GeneratedPage {
+    type Data = Bytes;
+    type DataGzip = Bytes;
+    type DataBr = Bytes;
+    type DataZstd = Bytes;
+    type MimeType = &'static str;
+    type ETag = HeaderStr;
+    type LastModified = HeaderStr;
+
+    fn data(&self) -> Self::Data {
+        self.data.clone()
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        None
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        None
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        None
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        None
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        None
+    }
+
+    fn etag(&self) -> Self::ETag {
+        self.etag.clone()
+    }
+
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        Some(self.mime_type)
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        Some(self.etag.header_value().clone())
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::listing::{escape_html, escape_json, Listing, ListingEntry};
+
+    #[allow(dead_code)]
+    fn listing() -> Listing {
+        Listing {
+            path: "/files/".to_string(),
+            entries: vec![
+                ListingEntry {
+                    name: "logs".to_string(),
+                    is_directory: true,
+                    size: None,
+                    last_modified_timestamp: None,
+                },
+                ListingEntry {
+                    name: "<b>report \"1\".txt".to_string(),
+                    is_directory: false,
+                    size: Some(42),
+                    last_modified_timestamp: Some(1700000000),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn html_is_escaped() {
+        let html = listing().to_html();
+        assert!(html.contains("<a href=\"./logs/\">logs/</a>"));
+        assert!(html.contains(
+            "<a href=\"./%3Cb%3Ereport%20%221%22.txt\">&lt;b&gt;report &quot;1&quot;.txt</a>"
+        ));
+        assert!(html.contains("<td>42</td><td>2023-11-14T22:13:20+00:00</td>"));
+        assert!(html.contains("<a href=\"../\">"));
+    }
+
+    #[test]
+    fn json_is_escaped() {
+        assert_eq!(
+            listing().to_json(),
+            r#"{"path":"/files/","entries":[{"name":"logs","type":"directory"},{"name":"<b>report \"1\".txt","type":"file","size":42,"last_modified":"2023-11-14T22:13:20+00:00"}]}"#
+        );
+    }
+
+    #[test]
+    fn control_characters_are_escaped() {
+        assert_eq!(escape_json("a\u{1}b\\"), r#""a\u0001b\\""#);
+        assert_eq!(escape_html("'&'"), "&#39;&amp;&#39;");
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use actix_web::http::header::HeaderValue;
+
+/// A switch to serve a maintenance page for every path, with a
+/// `503 Service Unavailable` status.
+///
+/// Clones of a `MaintenanceMode` share the same switch, so you can keep one
+/// to turn maintenance on and off while the server is running.
+///
+/// ```ignore
+/// use actix_web_rust_embed_responder::{EmbedService, MaintenanceMode, RustEmbedSource};
+///
+/// let maintenance = MaintenanceMode::new("maintenance.html")
+///     .retry_after(Duration::from_secs(600));
+/// let service = EmbedService::new("/", RustEmbedSource::<Embed>::new())
+///     .maintenance_mode(maintenance.clone());
+///
+/// // later, while migrating
+/// maintenance.enable();
+/// ```
+#[derive(Clone, Debug)]
+pub struct MaintenanceMode {
+    enabled: Arc<AtomicBool>,
+    page: String,
+    retry_after: Option<Duration>,
+}
+
+impl MaintenanceMode {
+    /// Serve `page` from the embed while maintenance is enabled. Maintenance
+    /// starts out disabled.
+    pub fn new(page: impl Into<String>) -> Self {
+        MaintenanceMode {
+            enabled: Arc::new(AtomicBool::new(false)),
+            page: page.into().trim_start_matches('/').to_string(),
+            retry_after: None,
+        }
+    }
+
+    /// Send a `Retry-After` header, telling clients when to try again.
+    pub fn retry_after(mut self, duration: Duration) -> Self {
+        self.retry_after = Some(duration);
+        self
+    }
+
+    /// Start serving the maintenance page.
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Go back to serving files normally.
+    pub fn disable(&self) {
+        self.enabled.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether the maintenance page is being served.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn page(&self) -> &str {
+        &self.page
+    }
+
+    pub(crate) fn retry_after_header(&self) -> Option<HeaderValue> {
+        self.retry_after
+            .map(|duration| HeaderValue::from(duration.as_secs()))
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use std::time::Duration;
+
+    use crate::maintenance::MaintenanceMode;
+
+    #[test]
+    fn clones_share_the_switch() {
+        let mode = MaintenanceMode::new("/maintenance.html");
+        let handle = mode.clone();
+        assert!(!mode.is_enabled());
+        handle.enable();
+        assert!(mode.is_enabled());
+        handle.disable();
+        assert!(!mode.is_enabled());
+        assert_eq!(mode.page(), "maintenance.html");
+    }
+
+    #[test]
+    fn retry_after_is_in_seconds() {
+        let mode = MaintenanceMode::new("maintenance.html");
+        assert!(mode.retry_after_header().is_none());
+        let mode = mode.retry_after(Duration::from_millis(90_500));
+        assert_eq!(mode.retry_after_header().unwrap(), "90");
+    }
+}
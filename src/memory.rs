@@ -0,0 +1,359 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use actix_web::{http::header::HeaderValue, web::Bytes};
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "compression-zstd")]
+use crate::compress::encode_zstd;
+use crate::{
+    compress::{encode_br, encode_gzip},
+    embed::EmbedRespondable,
+    headers::{FileHeaders, HeaderStr},
+    source::EmbedSource,
+};
+
+/// A file created at runtime, like a config file generated at startup.
+///
+/// The file gets the same ETag as a `rust-embed` file with the same
+/// contents, the sha256 hash of the data, so it is served with the same
+/// caching and compression as embedded files. Cloning it is cheap.
+///
+/// ```
+/// use actix_web_rust_embed_responder::MemoryFile;
+///
+/// let config = MemoryFile::new(r#"{"api": "/api/v2"}"#)
+///     .with_path("config.json")
+///     .with_mime_type("application/json")
+///     .precompress();
+/// ```
+#[derive(Clone, Debug)]
+pub struct MemoryFile {
+    data: Bytes,
+    data_gzip: Option<Bytes>,
+    data_br: Option<Bytes>,
+    data_zstd: Option<Bytes>,
+    path: Option<Arc<str>>,
+    mime_type: Option<Arc<str>>,
+    last_modified_timestamp: Option<i64>,
+    hash: [u8; 32],
+    headers: FileHeaders,
+}
+
+impl MemoryFile {
+    /// A file with these contents.
+    pub fn new(data: impl Into<Bytes>) -> Self {
+        let data = data.into();
+        let hash = Sha256::digest(&data).into();
+        MemoryFile {
+            data,
+            data_gzip: None,
+            data_br: None,
+            data_zstd: None,
+            path: None,
+            mime_type: None,
+            last_modified_timestamp: None,
+            hash,
+            headers: FileHeaders::new(hash, None),
+        }
+    }
+
+    /// The path of the file, like `assets/config.json`. This is used for the
+    /// mime type overrides, and to guess the mime type with the `mime-guess`
+    /// feature.
+    pub fn with_path(mut self, path: impl AsRef<str>) -> Self {
+        self.path = Some(path.as_ref().trim_start_matches('/').into());
+        self
+    }
+
+    /// The mime type of the file. It is used even if there is a mime
+    /// override for the extension of the path.
+    ///
+    /// A mime type with characters that can't be in a header is ignored, and
+    /// the previous mime type is kept.
+    pub fn with_mime_type(mut self, mime_type: impl AsRef<str>) -> Self {
+        let mime_type = mime_type.as_ref();
+        if HeaderStr::new(mime_type).is_some() {
+            self.mime_type = Some(mime_type.into());
+        }
+        self
+    }
+
+    /// When the file was last modified, as a UNIX timestamp.
+    pub fn with_last_modified(mut self, timestamp: i64) -> Self {
+        self.last_modified_timestamp = Some(timestamp);
+        self.headers = FileHeaders::new(self.hash, Some(timestamp));
+        self
+    }
+
+    /// Compress the file now, instead of when it is first requested.
+    ///
+    /// The compressed data is kept with the file, so it doesn't need to be
+    /// cached separately. It is used when the response allows precompressed
+    /// data, which is the default. If compressing fails, the file is
+    /// compressed when it is requested instead.
+    pub fn precompress(mut self) -> Self {
+        self.data_gzip = encode_gzip(&self.data).ok().map(Into::into);
+        self.data_br = encode_br(&self.data).ok().map(Into::into);
+        #[cfg(feature = "compression-zstd")]
+        {
+            self.data_zstd = encode_zstd(&self.data).ok().map(Into::into);
+        }
+        self
+    }
+}
+
+impl EmbedRespondable for MemoryFile {
+    type Data = Bytes;
+    type DataGzip = Bytes;
+    type DataBr = Bytes;
+    type DataZstd = Bytes;
+    type MimeType = MemoryMimeType;
+    type ETag = HeaderStr;
+    type LastModified = HeaderStr;
+
+    fn data(&self) -> Self::Data {
+        self.data.clone()
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        self.data_gzip.clone()
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        self.data_br.clone()
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        self.data_zstd.clone()
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        self.last_modified_timestamp
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        self.headers.last_modified.clone()
+    }
+
+    fn etag(&self) -> Self::ETag {
+        self.headers.etag.clone()
+    }
+
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        if let Some(mime_type) = &self.mime_type {
+            return Some(MemoryMimeType::Set(mime_type.clone()));
+        }
+        #[cfg(feature = "mime-guess")]
+        if let Some(path) = &self.path {
+            return mime_guess::from_path(path.as_ref())
+                .first_raw()
+                .map(MemoryMimeType::Guessed);
+        }
+        None
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.path.as_deref().map(Cow::Borrowed)
+    }
+
+    fn has_explicit_mime_type(&self) -> bool {
+        self.mime_type.is_some()
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        Some(self.headers.etag.header_value().clone())
+    }
+
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        self.headers
+            .last_modified
+            .as_ref()
+            .map(|value| value.header_value().clone())
+    }
+}
+
+/// The mime type of a `MemoryFile`.
+pub enum MemoryMimeType {
+    /// Set with `with_mime_type`.
+    Set(Arc<str>),
+    /// Guessed from the path.
+    Guessed(&'static str),
+}
+
+impl AsRef<str> for MemoryMimeType {
+    fn as_ref(&self) -> &str {
+        match self {
+            MemoryMimeType::Set(mime_type) => mime_type,
+            MemoryMimeType::Guessed(mime_type) => mime_type,
+        }
+    }
+}
+
+/// A set of `MemoryFile`s that can be changed while the server is running.
+///
+/// Clones of a `MemoryFiles` share the same files, so you can keep one to
+/// update the files while another is used as an `EmbedSource`. Each update
+/// is atomic: a request gets either the old or the new version of a file,
+/// and `replace_all` swaps all the files at once.
+///
+/// ```ignore
+/// let files = MemoryFiles::new();
+/// files.insert("config.json", MemoryFile::new(config_json));
+/// App::new().service(EmbedService::new("/generated", files.clone()))
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryFiles {
+    files: Arc<RwLock<Arc<HashMap<String, MemoryFile>>>>,
+}
+
+impl MemoryFiles {
+    /// An empty set of files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file at this path, replacing the file that was there. The file
+    /// gets this path if it didn't have one.
+    pub fn insert(&self, path: impl AsRef<str>, file: MemoryFile) {
+        let path = path.as_ref().trim_start_matches('/');
+        let file = match file.path {
+            Some(_) => file,
+            None => file.with_path(path),
+        };
+        self.update(|files| {
+            files.insert(path.to_string(), file);
+        });
+    }
+
+    /// Remove the file at this path.
+    pub fn remove(&self, path: impl AsRef<str>) -> Option<MemoryFile> {
+        let path = path.as_ref().trim_start_matches('/');
+        let mut removed = None;
+        self.update(|files| removed = files.remove(path));
+        removed
+    }
+
+    /// Replace all the files at once.
+    pub fn replace_all(&self, files: impl IntoIterator<Item = (String, MemoryFile)>) {
+        let files = files
+            .into_iter()
+            .map(|(path, file)| {
+                let path = path.trim_start_matches('/').to_string();
+                let file = match file.path {
+                    Some(_) => file,
+                    None => file.with_path(&path),
+                };
+                (path, file)
+            })
+            .collect();
+        *self.files.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(files);
+    }
+
+    /// The file at this path.
+    pub fn get(&self, path: &str) -> Option<MemoryFile> {
+        self.snapshot().get(path.trim_start_matches('/')).cloned()
+    }
+
+    /// The current files. Updates after this don't change the snapshot.
+    fn snapshot(&self) -> Arc<HashMap<String, MemoryFile>> {
+        self.files.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn update(&self, change: impl FnOnce(&mut HashMap<String, MemoryFile>)) {
+        let mut files = self.files.write().unwrap_or_else(|e| e.into_inner());
+        change(Arc::make_mut(&mut files));
+    }
+}
+
+impl EmbedSource for MemoryFiles {
+    type File = MemoryFile;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        MemoryFiles::get(self, path)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        let snapshot = self.snapshot();
+        let paths: Vec<String> = snapshot.keys().cloned().collect();
+        Box::new(paths.into_iter().map(Cow::Owned))
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::{
+        embed::EmbedRespondable,
+        memory::{MemoryFile, MemoryFiles},
+        source::EmbedSource,
+    };
+
+    #[test]
+    fn etag_is_the_hash_of_the_data() {
+        let file = MemoryFile::new("hello");
+        // sha256 of "hello", base64 encoded without padding
+        assert_eq!(
+            file.etag().as_ref(),
+            "\"LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ\""
+        );
+        assert_eq!(
+            MemoryFile::new("hello").etag().as_ref(),
+            file.etag().as_ref()
+        );
+        assert_ne!(
+            MemoryFile::new("world").etag().as_ref(),
+            file.etag().as_ref()
+        );
+    }
+
+    #[test]
+    fn last_modified_is_formatted() {
+        let file = MemoryFile::new("hello").with_last_modified(1_700_000_000);
+        assert_eq!(file.last_modified_timestamp(), Some(1_700_000_000));
+        assert_eq!(
+            file.last_modified().unwrap().as_ref(),
+            "Tue, 14 Nov 2023 22:13:20 +0000"
+        );
+    }
+
+    #[test]
+    fn invalid_mime_types_are_ignored() {
+        let file = MemoryFile::new("{}")
+            .with_mime_type("application/json")
+            .with_mime_type("application/\njson");
+        assert_eq!(file.mime_type().unwrap().as_ref(), "application/json");
+        assert!(MemoryFile::new("{}")
+            .with_mime_type("text/\nplain")
+            .mime_type()
+            .is_none());
+    }
+
+    #[test]
+    fn precompress_keeps_compressed_data() {
+        let file = MemoryFile::new("hello hello hello hello");
+        assert!(file.data_br().is_none());
+        let file = file.precompress();
+        assert!(file.data_br().is_some());
+        assert!(file.data_gzip().is_some());
+    }
+
+    #[test]
+    fn registry_updates_are_shared() {
+        let files = MemoryFiles::new();
+        let handle = files.clone();
+        handle.insert("/config.json", MemoryFile::new("{}"));
+        let file = files.get("config.json").unwrap();
+        assert_eq!(file.name().as_deref(), Some("config.json"));
+
+        handle.replace_all([("other.json".to_string(), MemoryFile::new("[]"))]);
+        assert!(files.get("config.json").is_none());
+        assert!(files.get("other.json").is_some());
+        assert_eq!(files.iter().collect::<Vec<_>>(), vec!["other.json"]);
+
+        assert!(handle.remove("other.json").is_some());
+        assert!(files.get("other.json").is_none());
+    }
+}
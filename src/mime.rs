@@ -0,0 +1,235 @@
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::parse::parse_mime_essence;
+
+/// Corrections for mime types, and the charset to send for text files.
+///
+/// The mime type of a file is normally guessed from its extension when it is
+/// embedded. Some of these guesses are outdated or missing, so the table here
+/// is checked first. The defaults fix up a few common web formats, like
+/// sending `.mjs` files as `text/javascript`, and you can add your own:
+///
+/// ```
+/// use actix_web_rust_embed_responder::MimeOverrides;
+///
+/// let overrides = MimeOverrides::default()
+///     .with_extension("glb", "model/gltf-binary")
+///     .with_extension("md", "text/markdown");
+/// ```
+///
+/// Text-like types, such as `text/html` or `application/json`, will also get
+/// a `; charset=utf-8` parameter added unless they already have a charset.
+/// You can change or disable this with `with_charset` and `without_charset`.
+#[derive(Clone, Debug)]
+pub struct MimeOverrides {
+    by_extension: HashMap<String, Cow<'static, str>>,
+    charset: Option<Cow<'static, str>>,
+}
+
+/// Built-in corrections to the mime types guessed from file extensions.
+const DEFAULT_OVERRIDES: &[(&str, &str)] = &[
+    // RFC 9239 made `text/javascript` the correct type for javascript
+    ("js", "text/javascript"),
+    ("mjs", "text/javascript"),
+    ("cjs", "text/javascript"),
+    ("webmanifest", "application/manifest+json"),
+    ("wasm", "application/wasm"),
+    ("map", "application/json"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("avif", "image/avif"),
+];
+
+impl Default for MimeOverrides {
+    fn default() -> Self {
+        MimeOverrides {
+            by_extension: DEFAULT_OVERRIDES
+                .iter()
+                .map(|(extension, mime_type)| (extension.to_string(), Cow::Borrowed(*mime_type)))
+                .collect(),
+            charset: Some(Cow::Borrowed("utf-8")),
+        }
+    }
+}
+
+impl MimeOverrides {
+    /// No overrides, and no charset parameters. The mime types of the files
+    /// will be sent as they are.
+    pub fn none() -> Self {
+        MimeOverrides {
+            by_extension: HashMap::new(),
+            charset: None,
+        }
+    }
+
+    /// Send files with this extension with the given mime type.
+    ///
+    /// The extension should not include the dot, and is case insensitive.
+    pub fn with_extension(
+        mut self,
+        extension: impl AsRef<str>,
+        mime_type: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.by_extension
+            .insert(extension.as_ref().to_ascii_lowercase(), mime_type.into());
+        self
+    }
+
+    /// Don't override the mime type of files with this extension, including
+    /// any of the built-in overrides.
+    pub fn without_extension(mut self, extension: impl AsRef<str>) -> Self {
+        self.by_extension
+            .remove(&extension.as_ref().to_ascii_lowercase());
+        self
+    }
+
+    /// The charset to add to text-like mime types.
+    pub fn with_charset(mut self, charset: impl Into<Cow<'static, str>>) -> Self {
+        self.charset = Some(charset.into());
+        self
+    }
+
+    /// Don't add a charset to text-like mime types.
+    pub fn without_charset(mut self) -> Self {
+        self.charset = None;
+        self
+    }
+
+    /// The mime type to use for a file at this path, if it is overridden.
+    pub(crate) fn for_path(&self, path: &str) -> Option<&str> {
+        if self.by_extension.is_empty() {
+            return None;
+        }
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        match file_name.rsplit_once('.') {
+            // A file like `.htaccess` is hidden, and doesn't have an extension
+            Some((stem, extension)) if !stem.is_empty() => self
+                .by_extension
+                .get(&extension.to_ascii_lowercase())
+                .map(AsRef::as_ref),
+            _ => None,
+        }
+    }
+
+    /// The mime type with a charset added, if it needs one.
+    pub(crate) fn with_charset_parameter(&self, mime_type: &str) -> Option<String> {
+        let charset = self.charset.as_ref()?;
+        if !is_text_mime_type(parse_mime_essence(mime_type)) || has_charset_parameter(mime_type) {
+            return None;
+        }
+        Some(format!("{mime_type}; charset={charset}"))
+    }
+}
+
+lazy_static! {
+    pub(crate) static ref DEFAULT_MIME_OVERRIDES: Arc<MimeOverrides> =
+        Arc::new(MimeOverrides::default());
+}
+
+/// Text types, plus the application types that are really text.
+fn is_text_mime_type(essence: &str) -> bool {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r#"(?i)^(text/.*|application/(javascript|ecmascript|json|xml|[^/]+[+](json|xml))|image/svg[+]xml)$"#
+        )
+        .unwrap();
+    }
+    RE.is_match(essence)
+}
+
+fn has_charset_parameter(mime_type: &str) -> bool {
+    mime_type.split(';').skip(1).any(|parameter| {
+        parameter
+            .trim()
+            .get(..8)
+            .map(|name| name.eq_ignore_ascii_case("charset="))
+            .unwrap_or(false)
+    })
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::mime::MimeOverrides;
+
+    #[test]
+    fn default_overrides_fix_javascript_modules() {
+        let overrides = MimeOverrides::default();
+        assert_eq!(overrides.for_path("app.mjs"), Some("text/javascript"));
+        assert_eq!(
+            overrides.for_path("assets/site.webmanifest"),
+            Some("application/manifest+json")
+        );
+    }
+
+    #[test]
+    fn overrides_are_case_insensitive() {
+        let overrides = MimeOverrides::default().with_extension("GLB", "model/gltf-binary");
+        assert_eq!(overrides.for_path("model.glb"), Some("model/gltf-binary"));
+        assert_eq!(overrides.for_path("APP.MJS"), Some("text/javascript"));
+    }
+
+    #[test]
+    fn overrides_can_be_removed() {
+        let overrides = MimeOverrides::default().without_extension("js");
+        assert_eq!(overrides.for_path("app.js"), None);
+        assert_eq!(MimeOverrides::none().for_path("app.mjs"), None);
+    }
+
+    #[test]
+    fn unknown_and_missing_extensions_are_not_overridden() {
+        let overrides = MimeOverrides::default();
+        assert_eq!(overrides.for_path("index.html"), None);
+        assert_eq!(overrides.for_path("LICENSE"), None);
+        assert_eq!(overrides.for_path(".map"), None);
+        assert_eq!(overrides.for_path("dir.js/file"), None);
+    }
+
+    #[test]
+    fn charset_is_added_to_text() {
+        let overrides = MimeOverrides::default();
+        assert_eq!(
+            overrides.with_charset_parameter("text/html").as_deref(),
+            Some("text/html; charset=utf-8")
+        );
+        assert_eq!(
+            overrides
+                .with_charset_parameter("application/manifest+json")
+                .as_deref(),
+            Some("application/manifest+json; charset=utf-8")
+        );
+        assert_eq!(
+            overrides.with_charset_parameter("image/svg+xml").as_deref(),
+            Some("image/svg+xml; charset=utf-8")
+        );
+    }
+
+    #[test]
+    fn charset_is_not_added_to_binary() {
+        let overrides = MimeOverrides::default();
+        assert_eq!(overrides.with_charset_parameter("image/png"), None);
+        assert_eq!(overrides.with_charset_parameter("application/wasm"), None);
+    }
+
+    #[test]
+    fn existing_charset_is_kept() {
+        let overrides = MimeOverrides::default();
+        assert_eq!(
+            overrides.with_charset_parameter("text/plain; Charset=latin1"),
+            None
+        );
+    }
+
+    #[test]
+    fn charset_is_configurable() {
+        let overrides = MimeOverrides::default().with_charset("iso-8859-1");
+        assert_eq!(
+            overrides.with_charset_parameter("text/css").as_deref(),
+            Some("text/css; charset=iso-8859-1")
+        );
+        let overrides = MimeOverrides::default().without_charset();
+        assert_eq!(overrides.with_charset_parameter("text/css"), None);
+    }
+}
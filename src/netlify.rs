@@ -0,0 +1,440 @@
+use std::{collections::HashMap, fmt};
+
+use actix_web::{
+    http::{
+        header::{HeaderName, HeaderValue},
+        StatusCode,
+    },
+    HttpResponse,
+};
+use regex::{Captures, Regex};
+
+/// An error in a `_headers` or `_redirects` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetlifyConfigError {
+    /// The name of the file, `_headers` or `_redirects`.
+    pub file: &'static str,
+    /// The line with the error, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for NetlifyConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} line {}: {}", self.file, self.line, self.message)
+    }
+}
+
+impl std::error::Error for NetlifyConfigError {}
+
+pub(crate) const HEADERS_FILE: &str = "_headers";
+pub(crate) const REDIRECTS_FILE: &str = "_redirects";
+
+/// A path pattern like `/blog/:year/*`.
+#[derive(Debug)]
+struct Pattern(Regex);
+
+impl Pattern {
+    fn new(pattern: &str) -> Result<Self, String> {
+        if !pattern.starts_with('/') {
+            return Err(format!("path `{pattern}` has to start with a `/`"));
+        }
+        let pattern = trim_trailing_slash(pattern);
+        let mut regex = String::from("^");
+        let mut has_splat = false;
+        let mut names: Vec<&str> = Vec::new();
+        let mut rest = pattern;
+        while let Some(c) = rest.chars().next() {
+            if rest == "/*" {
+                // `/blog/*` also matches `/blog` itself
+                regex.push_str("(?:/(?P<splat>.*))?");
+                has_splat = true;
+                rest = "";
+            } else if c == '*' {
+                if has_splat {
+                    regex.push_str(".*");
+                } else {
+                    regex.push_str("(?P<splat>.*)");
+                    has_splat = true;
+                }
+                rest = &rest[1..];
+            } else if c == ':' && rest[1..].starts_with(is_name_start) {
+                let name = placeholder_name(&rest[1..]);
+                if name == "splat" || names.contains(&name) {
+                    return Err(format!("placeholder `:{name}` is used more than once"));
+                }
+                names.push(name);
+                regex.push_str(&format!("(?P<{name}>[^/]+)"));
+                rest = &rest[1 + name.len()..];
+            } else {
+                regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        regex.push('$');
+        Regex::new(&regex)
+            .map(Pattern)
+            .map_err(|error| error.to_string())
+    }
+
+    /// Match a path, which should start with a `/`.
+    fn captures<'a>(&self, path: &'a str) -> Option<Captures<'a>> {
+        self.0.captures(trim_trailing_slash(path))
+    }
+}
+
+fn trim_trailing_slash(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" => "/",
+        path => path,
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn placeholder_name(value: &str) -> &str {
+    let end = value
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(value.len());
+    &value[..end]
+}
+
+/// Replace the `:splat` and `:name` placeholders with what they matched.
+fn expand_placeholders(target: &str, pattern: &Pattern, captures: &Captures) -> String {
+    let mut expanded = String::with_capacity(target.len());
+    let mut rest = target;
+    while let Some(index) = rest.find(':') {
+        expanded.push_str(&rest[..index]);
+        let name = placeholder_name(&rest[index + 1..]);
+        let is_placeholder = pattern.0.capture_names().flatten().any(|n| n == name);
+        match captures.name(name) {
+            Some(value) if is_placeholder => expanded.push_str(value.as_str()),
+            // `/blog/*` matched `/blog`, so there is no splat
+            None if is_placeholder => {}
+            // Not a placeholder, like the port in `https://example.com:8080`
+            _ => {
+                expanded.push(':');
+                expanded.push_str(name);
+            }
+        }
+        rest = &rest[index + 1 + name.len()..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[derive(Debug)]
+struct HeaderRule {
+    pattern: Pattern,
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+#[derive(Debug)]
+struct RedirectRule {
+    pattern: Pattern,
+    target: String,
+    status: StatusCode,
+    force: bool,
+}
+
+/// A redirect or rewrite that matched a request.
+pub(crate) struct Redirect {
+    /// The path or URL to redirect to, with the placeholders filled in.
+    pub(crate) target: String,
+    pub(crate) status: StatusCode,
+    /// Whether to apply the redirect even if there is a file at the path.
+    pub(crate) force: bool,
+}
+
+impl Redirect {
+    /// Whether this serves the target in place of the path, instead of
+    /// redirecting to it.
+    pub(crate) fn is_rewrite(&self) -> bool {
+        !self.status.is_redirection()
+    }
+}
+
+/// The rules from the `_headers` and `_redirects` files of an embed.
+#[derive(Debug, Default)]
+pub(crate) struct NetlifyConfig {
+    headers: Vec<HeaderRule>,
+    redirects: Vec<RedirectRule>,
+}
+
+impl NetlifyConfig {
+    pub(crate) fn parse(
+        headers: Option<&str>,
+        redirects: Option<&str>,
+    ) -> Result<Self, NetlifyConfigError> {
+        Ok(NetlifyConfig {
+            headers: headers.map(parse_headers).transpose()?.unwrap_or_default(),
+            redirects: redirects
+                .map(parse_redirects)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// The first redirect that matches the path.
+    pub(crate) fn redirect_for(&self, path: &str) -> Option<Redirect> {
+        self.redirects.iter().find_map(|rule| {
+            rule.pattern.captures(path).map(|captures| Redirect {
+                target: expand_placeholders(&rule.target, &rule.pattern, &captures),
+                status: rule.status,
+                force: rule.force,
+            })
+        })
+    }
+
+    /// Add the headers for the path to the response. If several rules set
+    /// the same header, their values are joined together.
+    pub(crate) fn apply_headers(&self, path: &str, resp: &mut HttpResponse) {
+        let mut values: HashMap<&HeaderName, Vec<&[u8]>> = HashMap::new();
+        let mut order: Vec<&HeaderName> = Vec::new();
+        for rule in &self.headers {
+            if rule.pattern.captures(path).is_none() {
+                continue;
+            }
+            for (name, value) in &rule.headers {
+                let entry = values.entry(name).or_insert_with(|| {
+                    order.push(name);
+                    Vec::new()
+                });
+                entry.push(value.as_bytes());
+            }
+        }
+        for name in order {
+            let joined = values[name].join(&b", "[..]);
+            if let Ok(value) = HeaderValue::from_bytes(&joined) {
+                resp.headers_mut().insert(name.clone(), value);
+            }
+        }
+    }
+}
+
+fn parse_headers(contents: &str) -> Result<Vec<HeaderRule>, NetlifyConfigError> {
+    let error = |line: usize, message: String| NetlifyConfigError {
+        file: HEADERS_FILE,
+        line,
+        message,
+    };
+    let mut rules: Vec<HeaderRule> = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            let pattern = Pattern::new(trimmed).map_err(|message| error(number, message))?;
+            rules.push(HeaderRule {
+                pattern,
+                headers: Vec::new(),
+            });
+            continue;
+        }
+        let rule = rules
+            .last_mut()
+            .ok_or_else(|| error(number, "header is not under a path".to_string()))?;
+        let (name, value) = trimmed
+            .split_once(':')
+            .ok_or_else(|| error(number, format!("expected `Name: value`, got `{trimmed}`")))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| {
+            error(
+                number,
+                format!("`{}` is not a valid header name", name.trim()),
+            )
+        })?;
+        let value = HeaderValue::from_str(value.trim())
+            .map_err(|_| error(number, format!("the value of `{name}` is not valid")))?;
+        rule.headers.push((name, value));
+    }
+    Ok(rules)
+}
+
+fn parse_redirects(contents: &str) -> Result<Vec<RedirectRule>, NetlifyConfigError> {
+    let error = |line: usize, message: String| NetlifyConfigError {
+        file: REDIRECTS_FILE,
+        line,
+        message,
+    };
+    let mut rules = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (from, target) = match (parts.next(), parts.next()) {
+            (Some(from), Some(target)) => (from, target),
+            _ => {
+                return Err(error(
+                    number,
+                    "expected a path and where to send it".to_string(),
+                ))
+            }
+        };
+        let (status, force) = match parts.next() {
+            Some(status) => {
+                let (code, force) = match status.strip_suffix('!') {
+                    Some(code) => (code, true),
+                    None => (status, false),
+                };
+                let status = code
+                    .parse::<u16>()
+                    .ok()
+                    .and_then(|code| StatusCode::from_u16(code).ok())
+                    .ok_or_else(|| error(number, format!("`{status}` is not a status code")))?;
+                (status, force)
+            }
+            None => (StatusCode::MOVED_PERMANENTLY, false),
+        };
+        if let Some(condition) = parts.next() {
+            return Err(error(
+                number,
+                format!("conditions like `{condition}` are not supported"),
+            ));
+        }
+
+        let is_url = target.starts_with("http://") || target.starts_with("https://");
+        match status.as_u16() {
+            301 | 302 | 303 | 307 | 308 => {}
+            200 | 404 if is_url => {
+                return Err(error(
+                    number,
+                    "proxying to another site is not supported".to_string(),
+                ))
+            }
+            200 | 404 => {}
+            _ => {
+                return Err(error(
+                    number,
+                    format!("status code {} is not supported", status.as_u16()),
+                ))
+            }
+        }
+        if !is_url && !target.starts_with('/') {
+            return Err(error(
+                number,
+                format!("`{target}` has to be a URL or start with a `/`"),
+            ));
+        }
+        rules.push(RedirectRule {
+            pattern: Pattern::new(from).map_err(|message| error(number, message))?,
+            target: target.to_string(),
+            status,
+            force,
+        });
+    }
+    Ok(rules)
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::netlify::{NetlifyConfig, NetlifyConfigError, Pattern};
+    use actix_web::{http::StatusCode, HttpResponse};
+
+    #[test]
+    fn patterns_match_splats_and_placeholders() {
+        let pattern = Pattern::new("/blog/:year/*").unwrap();
+        let captures = pattern.captures("/blog/2024/hello/world").unwrap();
+        assert_eq!(&captures["year"], "2024");
+        assert_eq!(&captures["splat"], "hello/world");
+        assert!(pattern.captures("/blog/2024").is_some());
+        assert!(pattern.captures("/blog").is_none());
+        assert!(pattern.captures("/blogs/2024/x").is_none());
+    }
+
+    #[test]
+    fn trailing_slashes_are_ignored() {
+        let pattern = Pattern::new("/about/").unwrap();
+        assert!(pattern.captures("/about").is_some());
+        assert!(pattern.captures("/about/").is_some());
+        assert!(Pattern::new("/").unwrap().captures("/").is_some());
+    }
+
+    #[test]
+    fn redirects_expand_placeholders() {
+        let config = NetlifyConfig::parse(
+            None,
+            Some(
+                "# Comment\n\n/old/*  /new/:splat\n/posts/:year/:slug  /blog/:year-:slug  302!\n/go  https://example.com:8080/x  307\n",
+            ),
+        )
+        .unwrap();
+
+        let redirect = config.redirect_for("/old/a/b").unwrap();
+        assert_eq!(redirect.target, "/new/a/b");
+        assert_eq!(redirect.status, StatusCode::MOVED_PERMANENTLY);
+        assert!(!redirect.force);
+
+        let redirect = config.redirect_for("/posts/2024/hello").unwrap();
+        assert_eq!(redirect.target, "/blog/2024-hello");
+        assert_eq!(redirect.status, StatusCode::FOUND);
+        assert!(redirect.force);
+
+        let redirect = config.redirect_for("/old").unwrap();
+        assert_eq!(redirect.target, "/new/");
+
+        let redirect = config.redirect_for("/go").unwrap();
+        assert_eq!(redirect.target, "https://example.com:8080/x");
+
+        assert!(config.redirect_for("/other").is_none());
+    }
+
+    #[test]
+    fn headers_are_joined() {
+        let config = NetlifyConfig::parse(
+            Some("/*\n  X-Frame-Options: DENY\n  Link: </a.css>; rel=preload\n/app/*\n  Link: </b.js>; rel=preload\n"),
+            None,
+        )
+        .unwrap();
+        let mut resp = HttpResponse::Ok().finish();
+        config.apply_headers("/app/page", &mut resp);
+        assert_eq!(resp.headers().get("X-Frame-Options").unwrap(), "DENY");
+        assert_eq!(
+            resp.headers().get("Link").unwrap(),
+            "</a.css>; rel=preload, </b.js>; rel=preload"
+        );
+
+        let mut resp = HttpResponse::Ok().finish();
+        config.apply_headers("/index.html", &mut resp);
+        assert_eq!(resp.headers().get("Link").unwrap(), "</a.css>; rel=preload");
+    }
+
+    #[test]
+    fn header_errors_have_line_numbers() {
+        let error = NetlifyConfig::parse(Some("/*\n  X-Frame-Options DENY\n"), None).unwrap_err();
+        assert_eq!(error.file, "_headers");
+        assert_eq!(error.line, 2);
+
+        let error = NetlifyConfig::parse(Some("  X-Frame-Options: DENY\n"), None).unwrap_err();
+        assert_eq!(error.line, 1);
+
+        let error = NetlifyConfig::parse(Some("\n\nno-slash\n"), None).unwrap_err();
+        assert_eq!(error.line, 3);
+
+        let error = NetlifyConfig::parse(Some("/*\n  Bad Name: x\n"), None).unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn redirect_errors_have_line_numbers() {
+        for (contents, line) in [
+            ("/a\n", 1),
+            ("/a /b\n/c /d abc\n", 2),
+            ("/a /b 418\n", 1),
+            ("/api/* https://api.example.com/:splat 200\n", 1),
+            ("/a /b 301 Country=us\n", 1),
+            ("/a b\n", 1),
+            ("/:x/:x /b\n", 1),
+        ] {
+            let error = NetlifyConfig::parse(None, Some(contents)).unwrap_err();
+            assert_eq!(error.file, "_redirects", "{contents}");
+            assert_eq!(error.line, line, "{contents}");
+        }
+    }
+}
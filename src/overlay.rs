@@ -0,0 +1,262 @@
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    error::Error as StdError,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_web::{
+    body::{BodySize, MessageBody},
+    http::header::HeaderValue,
+    web::Bytes,
+};
+
+use crate::{embed::EmbedRespondable, source::EmbedSource};
+
+/// Several sources combined into one, where the files of the first source
+/// take precedence over the second.
+///
+/// The sources can be of different kinds, for example a `rust-embed` embed
+/// with per-customer overrides on top of a `rust-embed-for-web` embed with
+/// the base files. Every file is served with its own ETag and compressed
+/// data, exactly as it would be from its own source.
+///
+/// ```ignore
+/// use actix_web_rust_embed_responder::{EmbedService, Overlay, RustEmbedSource, WebEmbedSource};
+///
+/// let source = Overlay::new(RustEmbedSource::<Theme>::new(), WebEmbedSource::<Base>::new())
+///     .then(RustEmbedSource::<Fallback>::new());
+/// App::new().service(EmbedService::new("/", source))
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Overlay<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Overlay<A, B> {
+    /// Look up files in `first`, then in `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Overlay { first, second }
+    }
+
+    /// Add another source below all the others.
+    pub fn then<C>(self, next: C) -> Overlay<Self, C> {
+        Overlay::new(self, next)
+    }
+}
+
+impl<A: EmbedSource, B: EmbedSource> EmbedSource for Overlay<A, B>
+where
+    OverlayFile<A::File, B::File>: EmbedRespondable,
+{
+    type File = OverlayFile<A::File, B::File>;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        self.first
+            .get(path)
+            .map(OverlayFile::First)
+            .or_else(|| self.second.get(path).map(OverlayFile::Second))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        let mut seen = HashSet::new();
+        Box::new(
+            self.first
+                .iter()
+                .chain(self.second.iter())
+                .filter(move |path| seen.insert(path.to_string())),
+        )
+    }
+}
+
+/// A file from an `Overlay`, from either the first or the second source.
+pub enum OverlayFile<A, B> {
+    First(A),
+    Second(B),
+}
+
+/// A value of an `OverlayFile`, like its data or ETag.
+pub enum OverlayValue<A, B> {
+    First(A),
+    Second(B),
+}
+
+impl<A: AsRef<str>, B: AsRef<str>> AsRef<str> for OverlayValue<A, B> {
+    fn as_ref(&self) -> &str {
+        match self {
+            OverlayValue::First(value) => value.as_ref(),
+            OverlayValue::Second(value) => value.as_ref(),
+        }
+    }
+}
+
+impl<A: AsRef<[u8]>, B: AsRef<[u8]>> AsRef<[u8]> for OverlayValue<A, B> {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            OverlayValue::First(value) => value.as_ref(),
+            OverlayValue::Second(value) => value.as_ref(),
+        }
+    }
+}
+
+impl<A, B> MessageBody for OverlayValue<A, B>
+where
+    A: MessageBody + Unpin,
+    B: MessageBody + Unpin,
+{
+    type Error = Box<dyn StdError>;
+
+    fn size(&self) -> BodySize {
+        match self {
+            OverlayValue::First(body) => body.size(),
+            OverlayValue::Second(body) => body.size(),
+        }
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        match self.get_mut() {
+            OverlayValue::First(body) => Pin::new(body).poll_next(cx).map_err(Into::into),
+            OverlayValue::Second(body) => Pin::new(body).poll_next(cx).map_err(Into::into),
+        }
+    }
+
+    fn try_into_bytes(self) -> Result<Bytes, Self> {
+        match self {
+            OverlayValue::First(body) => body.try_into_bytes().map_err(OverlayValue::First),
+            OverlayValue::Second(body) => body.try_into_bytes().map_err(OverlayValue::Second),
+        }
+    }
+}
+
+impl<A, B> EmbedRespondable for OverlayFile<A, B>
+where
+    A: EmbedRespondable,
+    B: EmbedRespondable,
+    A::Data: Unpin,
+    A::DataGzip: Unpin,
+    A::DataBr: Unpin,
+    A::DataZstd: Unpin,
+    B::Data: Unpin,
+    B::DataGzip: Unpin,
+    B::DataBr: Unpin,
+    B::DataZstd: Unpin,
+{
+    type Data = OverlayValue<A::Data, B::Data>;
+    type DataGzip = OverlayValue<A::DataGzip, B::DataGzip>;
+    type DataBr = OverlayValue<A::DataBr, B::DataBr>;
+    type DataZstd = OverlayValue<A::DataZstd, B::DataZstd>;
+    type MimeType = OverlayValue<A::MimeType, B::MimeType>;
+    type ETag = OverlayValue<A::ETag, B::ETag>;
+    type LastModified = OverlayValue<A::LastModified, B::LastModified>;
+
+    fn data(&self) -> Self::Data {
+        match self {
+            OverlayFile::First(file) => OverlayValue::First(file.data()),
+            OverlayFile::Second(file) => OverlayValue::Second(file.data()),
+        }
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        match self {
+            OverlayFile::First(file) => file.data_gzip().map(OverlayValue::First),
+            OverlayFile::Second(file) => file.data_gzip().map(OverlayValue::Second),
+        }
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        match self {
+            OverlayFile::First(file) => file.data_br().map(OverlayValue::First),
+            OverlayFile::Second(file) => file.data_br().map(OverlayValue::Second),
+        }
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        match self {
+            OverlayFile::First(file) => file.data_zstd().map(OverlayValue::First),
+            OverlayFile::Second(file) => file.data_zstd().map(OverlayValue::Second),
+        }
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        match self {
+            OverlayFile::First(file) => file.last_modified_timestamp(),
+            OverlayFile::Second(file) => file.last_modified_timestamp(),
+        }
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        match self {
+            OverlayFile::First(file) => file.last_modified().map(OverlayValue::First),
+            OverlayFile::Second(file) => file.last_modified().map(OverlayValue::Second),
+        }
+    }
+
+    fn etag(&self) -> Self::ETag {
+        match self {
+            OverlayFile::First(file) => OverlayValue::First(file.etag()),
+            OverlayFile::Second(file) => OverlayValue::Second(file.etag()),
+        }
+    }
+
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        match self {
+            OverlayFile::First(file) => file.mime_type().map(OverlayValue::First),
+            OverlayFile::Second(file) => file.mime_type().map(OverlayValue::Second),
+        }
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        match self {
+            OverlayFile::First(file) => file.name(),
+            OverlayFile::Second(file) => file.name(),
+        }
+    }
+
+    fn has_explicit_mime_type(&self) -> bool {
+        match self {
+            OverlayFile::First(file) => file.has_explicit_mime_type(),
+            OverlayFile::Second(file) => file.has_explicit_mime_type(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            OverlayFile::First(file) => file.size(),
+            OverlayFile::Second(file) => file.size(),
+        }
+    }
+
+    fn load_data(&self) -> io::Result<()> {
+        match self {
+            OverlayFile::First(file) => file.load_data(),
+            OverlayFile::Second(file) => file.load_data(),
+        }
+    }
+
+    fn cache_key(&self) -> Cow<'_, str> {
+        match self {
+            OverlayFile::First(file) => file.cache_key(),
+            OverlayFile::Second(file) => file.cache_key(),
+        }
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        match self {
+            OverlayFile::First(file) => file.etag_header(),
+            OverlayFile::Second(file) => file.etag_header(),
+        }
+    }
+
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        match self {
+            OverlayFile::First(file) => file.last_modified_header(),
+            OverlayFile::Second(file) => file.last_modified_header(),
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::{borrow::Cow, io};
+
+use actix_web::http::header::HeaderValue;
+
+use crate::{
+    embed::EmbedRespondable,
+    headers::{FileHeaders, HeaderStr},
+};
+
+/// A file with some of its metadata replaced, like the ETag or the last
+/// modified date. The data and the precompressed versions still come from
+/// the file.
+///
+/// ```ignore
+/// use actix_web_rust_embed_responder::{IntoResponse, WithOverrides};
+///
+/// // Use the build time as the last modified date, and a weak ETag since the
+/// // file is transformed before it is sent.
+/// Embed::get(path)
+///     .map(|file| WithOverrides::new(file).with_last_modified(BUILD_TIME).with_weak_etag())
+///     .into_response()
+/// ```
+#[derive(Clone, Debug)]
+pub struct WithOverrides<T> {
+    file: T,
+    etag: Option<HeaderStr>,
+    last_modified_timestamp: Option<i64>,
+    last_modified: Option<HeaderStr>,
+    mime_type: Option<HeaderStr>,
+}
+
+impl<T: EmbedRespondable> WithOverrides<T> {
+    /// Wraps the file, without overriding anything yet.
+    pub fn new(file: T) -> Self {
+        WithOverrides {
+            file,
+            etag: None,
+            last_modified_timestamp: None,
+            last_modified: None,
+            mime_type: None,
+        }
+    }
+
+    /// The file being wrapped.
+    pub fn file(&self) -> &T {
+        &self.file
+    }
+
+    /// Use this tag as the ETag, like a version number. The tag is put in
+    /// quotes if it isn't already.
+    ///
+    /// A tag with characters that can't be in a header is ignored, and the
+    /// previous ETag is kept.
+    pub fn with_etag(mut self, tag: impl AsRef<str>) -> Self {
+        let tag = tag.as_ref();
+        let tag = if tag.starts_with('"') || tag.starts_with("W/\"") {
+            tag.to_string()
+        } else {
+            format!("\"{tag}\"")
+        };
+        if let Some(etag) = HeaderStr::new(tag) {
+            self.etag = Some(etag);
+        }
+        self
+    }
+
+    /// Mark the ETag as weak, for files that are changed before they are
+    /// sent. A weak ETag means the file is equivalent, not the same bytes.
+    ///
+    /// If the file's own ETag can't be in a header, it is kept as it is.
+    pub fn with_weak_etag(mut self) -> Self {
+        let etag = self.current_etag();
+        if !etag.starts_with("W/") {
+            self.set_etag(format!("W/{etag}"));
+        }
+        self
+    }
+
+    /// Shorten the ETag to at most this many characters, not counting the
+    /// quotes. This keeps headers small when the full hash isn't needed.
+    ///
+    /// If the file's own ETag can't be in a header, it is kept as it is.
+    pub fn with_short_etag(mut self, length: usize) -> Self {
+        let etag = self.current_etag();
+        let (weak, tag) = match etag.strip_prefix("W/") {
+            Some(tag) => ("W/", tag),
+            None => ("", etag.as_str()),
+        };
+        let tag = tag.trim_matches('"');
+        let tag = match tag.char_indices().nth(length) {
+            Some((end, _)) => &tag[..end],
+            None => tag,
+        };
+        self.set_etag(format!("{weak}\"{tag}\""));
+        self
+    }
+
+    /// Use this UNIX timestamp as the last modified date.
+    pub fn with_last_modified(mut self, timestamp: i64) -> Self {
+        self.last_modified_timestamp = Some(timestamp);
+        self.last_modified = FileHeaders::format_last_modified(timestamp);
+        self
+    }
+
+    /// Use this mime type for the file. It is used even if the service has
+    /// an extension override for the file's extension.
+    ///
+    /// A mime type with characters that can't be in a header is ignored, and
+    /// the previous mime type is kept.
+    pub fn with_mime_type(mut self, mime_type: impl AsRef<str>) -> Self {
+        if let Some(mime_type) = HeaderStr::new(mime_type.as_ref().to_string()) {
+            self.mime_type = Some(mime_type);
+        }
+        self
+    }
+
+    /// Replace the ETag, unless the new one can't be in a header.
+    fn set_etag(&mut self, etag: String) {
+        if let Some(etag) = HeaderStr::new(etag) {
+            self.etag = Some(etag);
+        }
+    }
+
+    fn current_etag(&self) -> String {
+        match &self.etag {
+            Some(etag) => etag.as_ref().to_string(),
+            None => self.file.etag().as_ref().to_string(),
+        }
+    }
+}
+
+/// A value of a `WithOverrides` file, either the file's own or the override.
+pub enum OverrideValue<V> {
+    /// The value of the wrapped file.
+    File(V),
+    /// The value set on the wrapper.
+    Override(HeaderStr),
+}
+
+impl<V: AsRef<str>> AsRef<str> for OverrideValue<V> {
+    fn as_ref(&self) -> &str {
+        match self {
+            OverrideValue::File(value) => value.as_ref(),
+            OverrideValue::Override(value) => value.as_ref(),
+        }
+    }
+}
+
+impl<T: EmbedRespondable> EmbedRespondable for WithOverrides<T> {
+    type Data = T::Data;
+    type DataGzip = T::DataGzip;
+    type DataBr = T::DataBr;
+    type DataZstd = T::DataZstd;
+    type MimeType = OverrideValue<T::MimeType>;
+    type ETag = OverrideValue<T::ETag>;
+    type LastModified = OverrideValue<T::LastModified>;
+
+    fn data(&self) -> Self::Data {
+        self.file.data()
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        self.file.data_gzip()
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        self.file.data_br()
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        self.file.data_zstd()
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        self.last_modified_timestamp
+            .or_else(|| self.file.last_modified_timestamp())
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        match (&self.last_modified_timestamp, &self.last_modified) {
+            (Some(_), last_modified) => last_modified.clone().map(OverrideValue::Override),
+            (None, _) => self.file.last_modified().map(OverrideValue::File),
+        }
+    }
+
+    fn etag(&self) -> Self::ETag {
+        match &self.etag {
+            Some(etag) => OverrideValue::Override(etag.clone()),
+            None => OverrideValue::File(self.file.etag()),
+        }
+    }
+
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        match &self.mime_type {
+            Some(mime_type) => Some(OverrideValue::Override(mime_type.clone())),
+            None => self.file.mime_type().map(OverrideValue::File),
+        }
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        self.file.name()
+    }
+
+    fn has_explicit_mime_type(&self) -> bool {
+        self.mime_type.is_some() || self.file.has_explicit_mime_type()
+    }
+
+    fn size(&self) -> usize {
+        self.file.size()
+    }
+
+    fn load_data(&self) -> io::Result<()> {
+        self.file.load_data()
+    }
+
+    fn cache_key(&self) -> Cow<'_, str> {
+        // The data is the same, so the cached data of the file still applies.
+        self.file.cache_key()
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        match &self.etag {
+            Some(etag) => Some(etag.header_value().clone()),
+            None => self.file.etag_header(),
+        }
+    }
+
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        match (&self.last_modified_timestamp, &self.last_modified) {
+            (Some(_), last_modified) => last_modified
+                .as_ref()
+                .map(|value| value.header_value().clone()),
+            (None, _) => self.file.last_modified_header(),
+        }
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::{embed::EmbedRespondable, memory::MemoryFile, overrides::WithOverrides};
+
+    #[test]
+    fn etags_can_be_replaced() {
+        let file = WithOverrides::new(MemoryFile::new("hello")).with_etag("v1");
+        assert_eq!(file.etag().as_ref(), "\"v1\"");
+        assert_eq!(file.etag_header().unwrap(), "\"v1\"");
+        let file = file.with_weak_etag();
+        assert_eq!(file.etag().as_ref(), "W/\"v1\"");
+        assert_eq!(file.with_weak_etag().etag().as_ref(), "W/\"v1\"");
+    }
+
+    #[test]
+    fn etags_can_be_shortened() {
+        let file = WithOverrides::new(MemoryFile::new("hello")).with_short_etag(8);
+        assert_eq!(file.etag().as_ref(), "\"LPJNul+w\"");
+        let file = file.with_weak_etag().with_short_etag(4);
+        assert_eq!(file.etag().as_ref(), "W/\"LPJN\"");
+        // The cached compressed data still belongs to the original file.
+        assert_eq!(file.cache_key(), MemoryFile::new("hello").etag().as_ref());
+    }
+
+    #[test]
+    fn last_modified_and_mime_type_can_be_replaced() {
+        let file = WithOverrides::new(MemoryFile::new("hello").with_last_modified(0))
+            .with_last_modified(1_700_000_000)
+            .with_mime_type("text/plain");
+        assert_eq!(file.last_modified_timestamp(), Some(1_700_000_000));
+        assert_eq!(
+            file.last_modified().unwrap().as_ref(),
+            "Tue, 14 Nov 2023 22:13:20 +0000"
+        );
+        assert_eq!(
+            file.last_modified_header().unwrap(),
+            "Tue, 14 Nov 2023 22:13:20 +0000"
+        );
+        assert_eq!(file.mime_type().unwrap().as_ref(), "text/plain");
+        assert_eq!(file.data(), "hello");
+    }
+
+    #[test]
+    fn invalid_values_are_ignored() {
+        let file = WithOverrides::new(MemoryFile::new("hello"))
+            .with_etag("v1")
+            .with_etag("v\n2")
+            .with_mime_type("text/plain")
+            .with_mime_type("text/\nplain");
+        assert_eq!(file.etag().as_ref(), "\"v1\"");
+        assert_eq!(file.mime_type().unwrap().as_ref(), "text/plain");
+    }
+}
@@ -10,6 +10,16 @@ pub(crate) fn parse_accept_encoding_value(value: &HeaderValue) -> Option<Vec<&st
     parse_comma_seperated_list(value, parse_single_encoding_value)
 }
 
+/// The media ranges in an `Accept` header, except the ones the client refuses with `q=0`.
+pub(crate) fn parse_accept_value(value: &HeaderValue) -> Option<Vec<&str>> {
+    parse_comma_seperated_list(value, parse_single_accept_value)
+}
+
+/// The mime type without any parameters, e.g. `text/html` for `text/html; charset=utf-8`.
+pub(crate) fn parse_mime_essence(value: &str) -> &str {
+    value.split(';').next().unwrap_or(value).trim()
+}
+
 fn parse_comma_seperated_list(
     value: &HeaderValue,
     parse_item: fn(&str) -> Option<&str>,
@@ -39,3 +49,22 @@ fn parse_single_encoding_value(value: &str) -> Option<&str> {
         .and_then(|v| v.name("value"))
         .map(|v| v.as_str())
 }
+
+fn parse_single_accept_value(value: &str) -> Option<&str> {
+    let media_range = parse_mime_essence(value);
+    let refused =
+        value
+            .split(';')
+            .skip(1)
+            .any(|parameter| match parameter.trim().split_once('=') {
+                Some((name, q)) if name.trim().eq_ignore_ascii_case("q") => {
+                    q.trim().parse::<f32>().map(|q| q <= 0.0).unwrap_or(false)
+                }
+                _ => false,
+            });
+    if media_range.is_empty() || refused {
+        None
+    } else {
+        Some(media_range)
+    }
+}
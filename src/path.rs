@@ -0,0 +1,350 @@
+use std::{
+    fmt,
+    future::{ready, Ready},
+    ops::Deref,
+};
+
+use actix_web::{dev::Payload, http::StatusCode, FromRequest, HttpRequest, ResponseError};
+use percent_encoding::percent_decode_str;
+
+/// The path of a file inside an embed, safe to pass to `Embed::get`.
+///
+/// Use this in your handlers instead of `web::Path<String>`. The path is
+/// percent-decoded, and normalized so that `.` segments and duplicate
+/// slashes are removed and `..` segments are resolved. Requests that try to
+/// escape the root of the embed with `..`, or that have NUL bytes or
+/// backslashes in the path, are rejected with a `400 Bad Request`.
+///
+/// ```ignore
+/// use actix_web::get;
+/// use actix_web_rust_embed_responder::{EmbedPath, EmbedResponse, IntoResponse};
+///
+/// #[get("/{path:.*}")]
+/// async fn serve_assets(path: EmbedPath) -> EmbedResponse<EmbeddedFile> {
+///     Embed::get(&path).into_response()
+/// }
+/// ```
+///
+/// If the route has a `{path}` parameter, that is what gets extracted.
+/// Otherwise it is the whole path of the request. Requests for a directory,
+/// like `/` or `/docs/`, get the index file in that directory. You can
+/// change this, or strip a prefix from the path, with `EmbedPathConfig`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmbedPath(String);
+
+impl EmbedPath {
+    /// The path, as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The path, as an owned string.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for EmbedPath {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for EmbedPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for EmbedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Settings for the `EmbedPath` extractor.
+///
+/// Add this to your app with `app_data` to change the settings for all the
+/// handlers in it:
+///
+/// ```
+/// use actix_web::App;
+/// use actix_web_rust_embed_responder::EmbedPathConfig;
+///
+/// App::new().app_data(EmbedPathConfig::default().prefix("/static"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct EmbedPathConfig {
+    prefix: Vec<String>,
+    index_file: Option<String>,
+}
+
+impl Default for EmbedPathConfig {
+    fn default() -> Self {
+        EmbedPathConfig {
+            prefix: Vec::new(),
+            index_file: Some("index.html".to_string()),
+        }
+    }
+}
+
+impl EmbedPathConfig {
+    /// Remove this prefix from the path. Requests for paths outside of the
+    /// prefix are rejected with a `404 Not Found`.
+    pub fn prefix(mut self, prefix: impl AsRef<str>) -> Self {
+        self.prefix = prefix
+            .as_ref()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        self
+    }
+
+    /// The file to use for requests to a directory, `index.html` by default.
+    pub fn index_file(mut self, index_file: impl Into<String>) -> Self {
+        self.index_file = Some(index_file.into());
+        self
+    }
+
+    /// Keep the paths of directories as they are, like `docs/` or an empty
+    /// path for the root.
+    pub fn without_index_file(mut self) -> Self {
+        self.index_file = None;
+        self
+    }
+}
+
+/// The reasons a path can be rejected by `EmbedPath`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmbedPathError {
+    /// The path is not valid UTF-8 after percent-decoding.
+    InvalidEncoding,
+    /// The path tries to go above the root with `..`, or is an absolute
+    /// Windows path.
+    Traversal,
+    /// The path contains a NUL byte.
+    NulByte,
+    /// The path contains a backslash.
+    Backslash,
+    /// The path is not inside the configured prefix.
+    OutsidePrefix,
+}
+
+impl fmt::Display for EmbedPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EmbedPathError::InvalidEncoding => "path is not valid UTF-8",
+            EmbedPathError::Traversal => "path goes outside of the root",
+            EmbedPathError::NulByte => "path contains a NUL byte",
+            EmbedPathError::Backslash => "path contains a backslash",
+            EmbedPathError::OutsidePrefix => "path is outside of the prefix",
+        })
+    }
+}
+
+impl std::error::Error for EmbedPathError {}
+
+impl ResponseError for EmbedPathError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            EmbedPathError::OutsidePrefix => StatusCode::NOT_FOUND,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// Decode and normalize a path from a URL.
+///
+/// The result has no leading `/`, and ends with a `/` if the path was for a
+/// directory. The root is an empty path.
+pub(crate) fn normalize_path(path: &str) -> Result<String, EmbedPathError> {
+    let decoded = percent_decode_str(path)
+        .decode_utf8()
+        .map_err(|_| EmbedPathError::InvalidEncoding)?;
+    if decoded.contains('\0') {
+        return Err(EmbedPathError::NulByte);
+    }
+    if decoded.contains('\\') {
+        return Err(EmbedPathError::Backslash);
+    }
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in decoded.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop().ok_or(EmbedPathError::Traversal)?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    // `C:` would make the path absolute when joined with a folder on Windows,
+    // which is what the debug builds do to read the files from disk.
+    if let Some(first) = segments.first() {
+        let bytes = first.as_bytes();
+        if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+            return Err(EmbedPathError::Traversal);
+        }
+    }
+
+    let mut normalized = segments.join("/");
+    let is_directory = matches!(decoded.rsplit('/').next(), Some("" | "." | ".."));
+    if is_directory && !normalized.is_empty() {
+        normalized.push('/');
+    }
+    Ok(normalized)
+}
+
+impl EmbedPathConfig {
+    fn extract(&self, req: &HttpRequest) -> Result<EmbedPath, EmbedPathError> {
+        let raw = req.match_info().get("path").unwrap_or_else(|| req.path());
+        let mut path = normalize_path(raw)?;
+
+        if !self.prefix.is_empty() {
+            let mut rest = path.as_str();
+            for segment in &self.prefix {
+                rest = match rest.strip_prefix(segment.as_str()) {
+                    Some("") => "",
+                    Some(rest) => rest
+                        .strip_prefix('/')
+                        .ok_or(EmbedPathError::OutsidePrefix)?,
+                    None => return Err(EmbedPathError::OutsidePrefix),
+                };
+            }
+            path = rest.to_string();
+        }
+
+        if path.is_empty() || path.ends_with('/') {
+            if let Some(index_file) = &self.index_file {
+                path.push_str(index_file);
+            }
+        }
+        Ok(EmbedPath(path))
+    }
+}
+
+impl FromRequest for EmbedPath {
+    type Error = EmbedPathError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let result = match req.app_data::<EmbedPathConfig>() {
+            Some(config) => config.extract(req),
+            None => EmbedPathConfig::default().extract(req),
+        };
+        ready(result)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::path::{normalize_path, EmbedPathError};
+
+    #[test]
+    fn plain_paths_are_unchanged() {
+        assert_eq!(normalize_path("index.html"), Ok("index.html".to_string()));
+        assert_eq!(
+            normalize_path("assets/app.js"),
+            Ok("assets/app.js".to_string())
+        );
+        assert_eq!(normalize_path(""), Ok("".to_string()));
+    }
+
+    #[test]
+    fn paths_are_decoded() {
+        assert_eq!(
+            normalize_path("my%20file.txt"),
+            Ok("my file.txt".to_string())
+        );
+        assert_eq!(normalize_path("%E2%9C%93.txt"), Ok("✓.txt".to_string()));
+        assert_eq!(normalize_path("a%2Fb"), Ok("a/b".to_string()));
+    }
+
+    #[test]
+    fn paths_are_decoded_once() {
+        assert_eq!(normalize_path("%252e%252e/x"), Ok("%2e%2e/x".to_string()));
+    }
+
+    #[test]
+    fn dots_and_slashes_are_normalized() {
+        assert_eq!(normalize_path("/a//b/./c"), Ok("a/b/c".to_string()));
+        assert_eq!(normalize_path("a/b/../c"), Ok("a/c".to_string()));
+        assert_eq!(normalize_path("docs/"), Ok("docs/".to_string()));
+        assert_eq!(normalize_path("docs/."), Ok("docs/".to_string()));
+        assert_eq!(normalize_path("docs/sub/.."), Ok("docs/".to_string()));
+        assert_eq!(normalize_path("./"), Ok("".to_string()));
+        assert_eq!(normalize_path("a/.."), Ok("".to_string()));
+    }
+
+    #[test]
+    fn dots_inside_names_are_kept() {
+        assert_eq!(normalize_path("..a/b..c"), Ok("..a/b..c".to_string()));
+        assert_eq!(normalize_path(".hidden"), Ok(".hidden".to_string()));
+        assert_eq!(normalize_path("..."), Ok("...".to_string()));
+    }
+
+    #[test]
+    fn traversal_is_rejected() {
+        for path in [
+            "..",
+            "../etc/passwd",
+            "/../etc/passwd",
+            "a/../../etc/passwd",
+            "%2e%2e/etc/passwd",
+            "%2E%2E%2Fetc%2Fpasswd",
+            ".%2e/etc/passwd",
+            "a/%2e%2e/%2e%2e/b",
+            "C:/Windows/win.ini",
+            "c%3A/Windows/win.ini",
+        ] {
+            assert_eq!(
+                normalize_path(path),
+                Err(EmbedPathError::Traversal),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn nul_bytes_are_rejected() {
+        for path in ["index.html%00.png", "%00", "a/%00/b"] {
+            assert_eq!(normalize_path(path), Err(EmbedPathError::NulByte), "{path}");
+        }
+    }
+
+    #[test]
+    fn backslashes_are_rejected() {
+        for path in [
+            "..\\etc\\passwd",
+            "..%5cetc%5cpasswd",
+            "a%5C..%5C..%5Cb",
+            "\\\\server\\share",
+        ] {
+            assert_eq!(
+                normalize_path(path),
+                Err(EmbedPathError::Backslash),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_encoding_is_rejected() {
+        for path in ["%ff", "%c0%ae%c0%ae/etc/passwd", "%e2%9c"] {
+            assert_eq!(
+                normalize_path(path),
+                Err(EmbedPathError::InvalidEncoding),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn broken_escapes_are_kept() {
+        assert_eq!(normalize_path("100%"), Ok("100%".to_string()));
+        assert_eq!(normalize_path("%zz"), Ok("%zz".to_string()));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::glob::Glob;
+
+/// Rules for which paths in an embed can be served.
+///
+/// Everything that gets embedded is served by default, including files like
+/// `.DS_Store` or source maps that happen to be in the assets folder. With
+/// these rules you can deny paths matching a glob pattern, and allow some of
+/// them again. The rules are checked in order, and the last one that matches
+/// a path decides. Denied paths get a `404 Not Found`, the same as a file
+/// that doesn't exist.
+///
+/// ```
+/// use actix_web_rust_embed_responder::PathRules;
+///
+/// let rules = PathRules::production()
+///     .deny("README.md")
+///     .deny("/drafts/")
+///     .allow("/drafts/preview.html");
+/// assert!(!rules.is_allowed("assets/app.js.map"));
+/// assert!(rules.is_allowed("drafts/preview.html"));
+/// ```
+///
+/// A pattern without a `/` matches a file or directory with that name
+/// anywhere, so `.*` denies `.env` as well as `.git/config`. A pattern with a
+/// `/` matches from the root of the embed, and `**` matches any number of
+/// directories, like `docs/**/*.md`.
+#[derive(Clone, Debug, Default)]
+pub struct PathRules {
+    rules: Vec<(Glob, bool)>,
+}
+
+impl PathRules {
+    /// Allow all paths.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hide the files that usually shouldn't be served in production: source
+    /// maps, and hidden files and directories like `.DS_Store` or `.git`.
+    /// The `.well-known` directory is still allowed.
+    pub fn production() -> Self {
+        PathRules::new()
+            .deny(".*")
+            .allow("/.well-known/")
+            .deny("*.map")
+    }
+
+    /// Deny the paths matching this pattern.
+    pub fn deny(mut self, pattern: impl AsRef<str>) -> Self {
+        self.rules.push((Glob::new(pattern.as_ref()), false));
+        self
+    }
+
+    /// Allow the paths matching this pattern, even if they were denied by
+    /// an earlier rule.
+    pub fn allow(mut self, pattern: impl AsRef<str>) -> Self {
+        self.rules.push((Glob::new(pattern.as_ref()), true));
+        self
+    }
+
+    /// Whether the path can be served. The path is relative to the root of
+    /// the embed, like `assets/app.js`.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let path = path.trim_start_matches('/');
+        self.rules
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob.is_match(path))
+            .map(|(_, allowed)| *allowed)
+            .unwrap_or(true)
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::rules::PathRules;
+
+    #[test]
+    fn everything_is_allowed_by_default() {
+        let rules = PathRules::new();
+        assert!(rules.is_allowed(".env"));
+        assert!(rules.is_allowed("app.js.map"));
+    }
+
+    #[test]
+    fn production_hides_dotfiles_and_source_maps() {
+        let rules = PathRules::production();
+        assert!(!rules.is_allowed(".DS_Store"));
+        assert!(!rules.is_allowed("assets/.DS_Store"));
+        assert!(!rules.is_allowed(".git/config"));
+        assert!(!rules.is_allowed("assets/app.js.map"));
+        assert!(!rules.is_allowed(".well-known/app.js.map"));
+        assert!(rules.is_allowed(".well-known/security.txt"));
+        assert!(rules.is_allowed("assets/app.js"));
+        assert!(rules.is_allowed("index.html"));
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let rules = PathRules::new()
+            .deny("/drafts/")
+            .allow("/drafts/preview.html");
+        assert!(!rules.is_allowed("drafts/post.html"));
+        assert!(rules.is_allowed("drafts/preview.html"));
+
+        let rules = rules.deny("*.html");
+        assert!(!rules.is_allowed("drafts/preview.html"));
+    }
+
+    #[test]
+    fn directories_are_checked() {
+        let rules = PathRules::production();
+        assert!(!rules.is_allowed(".git/"));
+        assert!(!rules.is_allowed("/.git"));
+    }
+}
@@ -1,38 +1,20 @@
-use base64::{engine::general_purpose::STANDARD_NO_PAD as Base64Encoder, Engine};
-use chrono::TimeZone;
-use rust_embed::EmbeddedFile;
-use std::{borrow::Cow, ops::Deref};
-
-use crate::embed::{EmbedRespondable, EmbedResponse, IntoResponse};
-
-impl From<EmbeddedFile> for EmbedResponse<EmbeddedFile> {
-    fn from(file: EmbeddedFile) -> Self {
-        EmbedResponse {
-            file: Some(file),
-            compress: Default::default(),
-        }
-    }
-}
-
-impl From<Option<EmbeddedFile>> for EmbedResponse<EmbeddedFile> {
-    fn from(file: Option<EmbeddedFile>) -> Self {
-        EmbedResponse {
-            file,
-            compress: Default::default(),
-        }
-    }
-}
-
-impl IntoResponse<EmbeddedFile> for EmbeddedFile {
-    fn into_response(self) -> EmbedResponse<EmbeddedFile> {
-        self.into()
-    }
-}
-
-impl IntoResponse<EmbeddedFile> for Option<EmbeddedFile> {
-    fn into_response(self) -> EmbedResponse<EmbeddedFile> {
-        self.into()
-    }
+use actix_web::http::header::HeaderValue;
+use rust_embed::{EmbeddedFile, RustEmbed};
+use std::{borrow::Cow, marker::PhantomData, ops::Deref, sync::Arc};
+
+use crate::{
+    embed::{EmbedRespondable, EmbedResponse},
+    headers::{hashed_file_headers, FileHeaders},
+    source::EmbedSource,
+};
+
+/// rust-embed only gives us the hash and timestamp of the file, so the header
+/// values have to be formatted at runtime. We do that once per file and reuse
+/// the results for every response after that. `etag` and `last_modified` still
+/// return strings, the cached header values are used through `etag_header` and
+/// `last_modified_header`.
+fn file_headers(file: &EmbeddedFile) -> Arc<FileHeaders> {
+    hashed_file_headers(file.metadata.sha256_hash(), file.last_modified_timestamp())
 }
 
 impl EmbedRespondable for EmbeddedFile {
@@ -61,12 +43,10 @@ impl EmbedRespondable for EmbeddedFile {
     }
 
     fn last_modified(&self) -> Option<Self::LastModified> {
-        self.last_modified_timestamp().map(|timestamp| {
-            chrono::Utc
-                .timestamp_opt(timestamp, 0)
-                .unwrap()
-                .to_rfc2822()
-        })
+        file_headers(self)
+            .last_modified
+            .as_ref()
+            .map(|v| v.as_ref().to_string())
     }
 
     fn last_modified_timestamp(&self) -> Option<i64> {
@@ -78,17 +58,40 @@ impl EmbedRespondable for EmbeddedFile {
     }
 
     fn etag(&self) -> Self::ETag {
-        format!("\"{}\"", Base64Encoder.encode(self.metadata.sha256_hash()))
+        file_headers(self).etag.as_ref().to_string()
     }
 
+    #[cfg(not(feature = "mime-guess"))]
     fn mime_type(&self) -> Option<Self::MimeType> {
         // rust-embed doesn't include the filename for the embedded file, so we
-        // can't guess the mime type. We could add `xdg-mime` to guess based on
-        // contents, but it will require the shared mime database to be
-        // available at runtime. In any case, it's okay if we just let the
-        // browser guess the mime type.
+        // can't guess the mime type unless its `mime-guess` feature is
+        // enabled. We could add `xdg-mime` to guess based on contents, but it
+        // will require the shared mime database to be available at runtime.
+        // In any case, it's okay if we just let the browser guess the mime
+        // type.
         None
     }
+
+    #[cfg(feature = "mime-guess")]
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        // rust-embed guessed this from the file extension when embedding. It
+        // uses `application/octet-stream` when it couldn't guess, which we
+        // treat as unknown so the mime fallback can still sniff the file.
+        Some(self.metadata.mimetype())
+            .filter(|mime_type| *mime_type != "application/octet-stream")
+            .map(ToString::to_string)
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        Some(file_headers(self).etag.header_value().clone())
+    }
+
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        file_headers(self)
+            .last_modified
+            .as_ref()
+            .map(|v| v.header_value().clone())
+    }
 }
 
 impl Deref for EmbedResponse<EmbeddedFile> {
@@ -98,3 +101,46 @@ impl Deref for EmbedResponse<EmbeddedFile> {
         &self.file
     }
 }
+
+/// The files of a `rust-embed` embed, as an `EmbedSource`.
+///
+/// ```ignore
+/// use actix_web_rust_embed_responder::{EmbedSource, RustEmbedSource};
+///
+/// let source = RustEmbedSource::<Embed>::new();
+/// let file = source.get("index.html");
+/// ```
+pub struct RustEmbedSource<E: RustEmbed>(PhantomData<fn() -> E>);
+
+impl<E: RustEmbed> RustEmbedSource<E> {
+    /// The files of the embed `E`, like `RustEmbedSource::<Embed>::new()`.
+    pub fn new() -> Self {
+        RustEmbedSource(PhantomData)
+    }
+}
+
+impl<E: RustEmbed> Default for RustEmbedSource<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: RustEmbed> Clone for RustEmbedSource<E> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<E: RustEmbed + 'static> EmbedSource for RustEmbedSource<E> {
+    type File = EmbeddedFile;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        E::get(path)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        // Shortens the lifetime of the paths, which the boxed iterator can't do
+        #[allow(clippy::map_identity)]
+        Box::new(E::iter().map(|path| -> Cow<'_, str> { path }))
+    }
+}
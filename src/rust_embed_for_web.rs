@@ -1,14 +1,26 @@
 use crate::embed::{EmbedRespondable, EmbedResponse, IntoResponse};
+use crate::source::EmbedSource;
 use actix_web::body::MessageBody;
-use rust_embed_for_web::{DynamicFile, EmbedableFile, EmbeddedFile};
+use rust_embed_for_web::{DynamicFile, EmbedableFile, EmbeddedFile, RustEmbed};
+use std::{borrow::Cow, marker::PhantomData};
 
 #[cfg(all(debug_assertions, not(feature = "always-embed")))]
 /// This is an alias that changes whether it refers to a `DynamicFile` or
 /// `EmbeddedFile` based on whether it's in debug or release mode.
 ///
-/// This is necessary if you are trying to avoid using `dyn` trait objects.
-/// Check [this example](https://github.com/SeriousBug/actix-web-rust-embed-responder/blob/main/examples/rust_embed_for_web.rs)
-/// for details.
+/// This is necessary if you are trying to avoid using `dyn` trait objects,
+/// like when writing your own handler:
+///
+/// ```ignore
+/// #[route("/{path:.*}", method = "GET", method = "HEAD")]
+/// async fn handler(path: web::Path<String>) -> EmbedResponse<EmbedableFileResponse> {
+///     Embed::get(&path).into_response()
+/// }
+/// ```
+///
+/// If you don't mind a trait object, `DynEmbedResponse` works in both modes.
+/// To serve the whole embed, use `EmbedService` with a `WebEmbedSource`
+/// instead.
 pub type EmbedableFileResponse = WebEmbedableFile<DynamicFile>;
 
 // --> If you update the docs above, copy and paste it below too!
@@ -17,26 +29,30 @@ pub type EmbedableFileResponse = WebEmbedableFile<DynamicFile>;
 /// This is an alias that changes whether it refers to a `DynamicFile` or
 /// `EmbeddedFile` based on whether it's in debug or release mode.
 ///
-/// This is necessary if you are trying to avoid using `dyn` trait objects.
-/// Check [this example](https://github.com/SeriousBug/actix-web-rust-embed-responder/blob/main/examples/rust_embed_for_web.rs)
-/// for details.
+/// This is necessary if you are trying to avoid using `dyn` trait objects,
+/// like when writing your own handler:
+///
+/// ```ignore
+/// #[route("/{path:.*}", method = "GET", method = "HEAD")]
+/// async fn handler(path: web::Path<String>) -> EmbedResponse<EmbedableFileResponse> {
+///     Embed::get(&path).into_response()
+/// }
+/// ```
+///
+/// If you don't mind a trait object, `DynEmbedResponse` works in both modes.
+/// To serve the whole embed, use `EmbedService` with a `WebEmbedSource`
+/// instead.
 pub type EmbedableFileResponse = WebEmbedableFile<EmbeddedFile>;
 
 impl From<EmbeddedFile> for EmbedResponse<WebEmbedableFile<EmbeddedFile>> {
     fn from(file: EmbeddedFile) -> Self {
-        EmbedResponse {
-            file: Some(WebEmbedableFile(file)),
-            compress: Default::default(),
-        }
+        EmbedResponse::new(Some(WebEmbedableFile(file)))
     }
 }
 
 impl From<Option<EmbeddedFile>> for EmbedResponse<WebEmbedableFile<EmbeddedFile>> {
     fn from(file: Option<EmbeddedFile>) -> Self {
-        EmbedResponse {
-            file: file.map(WebEmbedableFile),
-            compress: Default::default(),
-        }
+        EmbedResponse::new(file.map(WebEmbedableFile))
     }
 }
 
@@ -54,19 +70,13 @@ impl IntoResponse<WebEmbedableFile<EmbeddedFile>> for Option<EmbeddedFile> {
 
 impl From<DynamicFile> for EmbedResponse<WebEmbedableFile<DynamicFile>> {
     fn from(file: DynamicFile) -> Self {
-        EmbedResponse {
-            file: Some(WebEmbedableFile(file)),
-            compress: Default::default(),
-        }
+        EmbedResponse::new(Some(WebEmbedableFile(file)))
     }
 }
 
 impl From<Option<DynamicFile>> for EmbedResponse<WebEmbedableFile<DynamicFile>> {
     fn from(file: Option<DynamicFile>) -> Self {
-        EmbedResponse {
-            file: file.map(WebEmbedableFile),
-            compress: Default::default(),
-        }
+        EmbedResponse::new(file.map(WebEmbedableFile))
     }
 }
 
@@ -87,7 +97,7 @@ impl IntoResponse<WebEmbedableFile<DynamicFile>> for Option<DynamicFile> {
 /// You shouldn't manually create objects of this struct, you should rely on
 /// `.into_response()` or `.into()` to create these from `DynamicFile`s or
 /// `EmbeddedFile`s you get from your `RustEmbed`.
-pub struct WebEmbedableFile<T: EmbedableFile>(T);
+pub struct WebEmbedableFile<T: EmbedableFile>(pub(crate) T);
 
 impl<T: EmbedableFile> EmbedRespondable for WebEmbedableFile<T>
 where
@@ -132,4 +142,55 @@ where
     fn mime_type(&self) -> Option<Self::MimeType> {
         self.0.mime_type()
     }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Owned(self.0.name().as_ref().to_owned()))
+    }
+}
+
+/// The files of a `rust-embed-for-web` embed, as an `EmbedSource`.
+///
+/// `rust-embed-for-web` doesn't have a way to list the embedded files, so
+/// `iter` doesn't return anything for this source.
+///
+/// ```ignore
+/// use actix_web_rust_embed_responder::{EmbedSource, WebEmbedSource};
+///
+/// let source = WebEmbedSource::<Embed>::new();
+/// let file = source.get("index.html");
+/// ```
+pub struct WebEmbedSource<E: RustEmbed>(PhantomData<fn() -> E>);
+
+impl<E: RustEmbed> WebEmbedSource<E> {
+    /// The files of the embed `E`, like `WebEmbedSource::<Embed>::new()`.
+    pub fn new() -> Self {
+        WebEmbedSource(PhantomData)
+    }
+}
+
+impl<E: RustEmbed> Default for WebEmbedSource<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: RustEmbed> Clone for WebEmbedSource<E> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<E: RustEmbed> EmbedSource for WebEmbedSource<E>
+where
+    <E::File as EmbedableFile>::Data: MessageBody,
+{
+    type File = WebEmbedableFile<E::File>;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        E::get(path).map(WebEmbedableFile)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        Box::new(std::iter::empty())
+    }
 }
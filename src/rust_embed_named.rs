@@ -0,0 +1,122 @@
+use actix_web::http::header::HeaderValue;
+use rust_embed::{EmbeddedFile, RustEmbed};
+use std::{borrow::Cow, ops::Deref};
+
+use crate::embed::{EmbedRespondable, EmbedResponse};
+
+/// A `rust-embed` file, along with the path it was loaded from.
+///
+/// Knowing the path allows the mime type to be guessed from the file
+/// extension, which is then used for the `Content-Type` header and to decide
+/// whether the file is worth compressing with `Compress::IfWellKnown`. You can
+/// get these from your embed with `get_named`:
+///
+/// ```ignore
+/// use actix_web_rust_embed_responder::{IntoResponse, RustEmbedExt};
+///
+/// Embed::get_named(path).into_response()
+/// ```
+pub struct NamedEmbeddedFile {
+    path: String,
+    file: EmbeddedFile,
+}
+
+impl NamedEmbeddedFile {
+    /// Wraps a file you got from your embed, along with the path you used to get it.
+    pub fn new(path: impl Into<String>, file: EmbeddedFile) -> Self {
+        NamedEmbeddedFile {
+            path: path.into(),
+            file,
+        }
+    }
+
+    /// The path this file was loaded from.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The embedded file.
+    pub fn file(&self) -> &EmbeddedFile {
+        &self.file
+    }
+}
+
+/// Adds `get_named` to all `rust-embed` embeds.
+pub trait RustEmbedExt {
+    /// Get a file out of the folder, remembering the path for mime type
+    /// guessing.
+    fn get_named(path: &str) -> Option<NamedEmbeddedFile>;
+}
+
+impl<T: RustEmbed> RustEmbedExt for T {
+    fn get_named(path: &str) -> Option<NamedEmbeddedFile> {
+        T::get(path).map(|file| NamedEmbeddedFile::new(path, file))
+    }
+}
+
+impl EmbedRespondable for NamedEmbeddedFile {
+    type Data = <EmbeddedFile as EmbedRespondable>::Data;
+    type DataGzip = <EmbeddedFile as EmbedRespondable>::DataGzip;
+    type DataBr = <EmbeddedFile as EmbedRespondable>::DataBr;
+    type DataZstd = <EmbeddedFile as EmbedRespondable>::DataZstd;
+    type ETag = <EmbeddedFile as EmbedRespondable>::ETag;
+    type LastModified = <EmbeddedFile as EmbedRespondable>::LastModified;
+    type MimeType = String;
+
+    fn data(&self) -> Self::Data {
+        self.file.data()
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        self.file.data_gzip()
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        self.file.data_br()
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        self.file.data_zstd()
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        self.file.last_modified_timestamp()
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        self.file.last_modified()
+    }
+
+    fn etag(&self) -> Self::ETag {
+        self.file.etag()
+    }
+
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        // The extension is the best information we have. If it's not one
+        // mime_guess knows, fall back to whatever rust-embed guessed.
+        mime_guess::from_path(&self.path)
+            .first_raw()
+            .map(ToString::to_string)
+            .or_else(|| self.file.mime_type())
+    }
+
+    fn name(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(&self.path))
+    }
+
+    fn etag_header(&self) -> Option<HeaderValue> {
+        self.file.etag_header()
+    }
+
+    fn last_modified_header(&self) -> Option<HeaderValue> {
+        self.file.last_modified_header()
+    }
+}
+
+impl Deref for EmbedResponse<NamedEmbeddedFile> {
+    type Target = Option<NamedEmbeddedFile>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.file
+    }
+}
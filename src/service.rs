@@ -0,0 +1,591 @@
+use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+use actix_web::{
+    body::{BodySize, MessageBody},
+    dev::{AppService, HttpServiceFactory},
+    http::{
+        header::{self, HeaderValue},
+        StatusCode,
+    },
+    web, HttpRequest, HttpResponse, Responder, ResponseError,
+};
+
+use crate::{
+    cache::CachePolicy,
+    compress::Compress,
+    compression_rules::CompressionRules,
+    config::embed_config,
+    embed::{send_error_page, EmbedRespondable, EmbedResponse, ResponseOptions},
+    error_pages::ErrorPages,
+    helper::encode_url_path,
+    listing::{list_directory, wants_json, GeneratedPage, Listing},
+    maintenance::MaintenanceMode,
+    netlify::{NetlifyConfig, NetlifyConfigError, Redirect, HEADERS_FILE, REDIRECTS_FILE},
+    path::normalize_path,
+    rules::PathRules,
+    source::EmbedSource,
+    spa::SpaFallback,
+    try_files::{content_location, directory_redirect_location, Candidate, TryFiles},
+};
+
+/// A service that serves all the files in an embed.
+///
+/// Instead of writing a handler yourself, you can mount the whole embed at a
+/// path. The service responds to `GET` and `HEAD` requests, and serves the
+/// index file for requests to a directory. Other methods get a
+/// `405 Method Not Allowed`.
+///
+/// ```ignore
+/// use actix_web::App;
+/// use actix_web_rust_embed_responder::{EmbedService, RustEmbedSource};
+///
+/// App::new().service(EmbedService::new("/static", RustEmbedSource::<Embed>::new()))
+/// ```
+pub struct EmbedService<S: EmbedSource> {
+    prefix: String,
+    source: S,
+    rules: PathRules,
+    index_file: Option<String>,
+    try_files: Option<TryFiles>,
+    directory_listing: bool,
+    spa_fallback: Option<SpaFallback>,
+    netlify: Option<NetlifyConfig>,
+    error_pages: Option<ErrorPages>,
+    maintenance: Option<MaintenanceMode>,
+    compress: Option<Compress>,
+    compression_rules: Option<Arc<CompressionRules>>,
+    cache: Option<CachePolicy>,
+}
+
+impl<S: EmbedSource> EmbedService<S> {
+    /// Serve the files of `source`, with the paths starting with `prefix`.
+    ///
+    /// The source gets the path of the file relative to the prefix. A
+    /// request for the prefix without the trailing slash, like `/static`, is
+    /// redirected to `/static/`. Use `RustEmbedSource` or `WebEmbedSource`
+    /// for the embeds of `rust-embed` and `rust-embed-for-web`, or your own
+    /// `EmbedSource`.
+    pub fn new(prefix: &str, source: S) -> Self {
+        EmbedService {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            source,
+            rules: PathRules::new(),
+            index_file: Some("index.html".to_string()),
+            try_files: None,
+            directory_listing: false,
+            spa_fallback: None,
+            netlify: None,
+            error_pages: None,
+            maintenance: None,
+            compress: None,
+            compression_rules: None,
+            cache: None,
+        }
+    }
+
+    /// The file to serve for requests to a directory, `index.html` by default.
+    pub fn index_file(mut self, index_file: impl Into<String>) -> Self {
+        self.index_file = Some(index_file.into());
+        self
+    }
+
+    /// Don't serve any file for requests to a directory.
+    pub fn without_index_file(mut self) -> Self {
+        self.index_file = None;
+        self
+    }
+
+    /// Only serve the paths allowed by these rules. Please see the PathRules
+    /// type for how the rules work.
+    pub fn path_rules(mut self, rules: PathRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Try a list of files for each request, instead of only the requested
+    /// path. Please see the TryFiles type for the details.
+    pub fn try_files(mut self, try_files: TryFiles) -> Self {
+        self.try_files = Some(try_files);
+        self
+    }
+
+    /// Generate a listing of the files for requests to a directory that
+    /// doesn't have an index file.
+    ///
+    /// The listing is a page with the names, sizes and last modified times
+    /// of the files, or JSON if the request accepts `application/json` and
+    /// not `text/html`. It is generated from `EmbedSource::iter`, so a source
+    /// that can't list its files will only have a listing for the root.
+    /// Requests for a directory without the `/` at the end are redirected to
+    /// the path with the `/`. To find those, a request for a path that
+    /// doesn't exist goes through all the files of the source, so missing
+    /// files take longer to answer in a large embed.
+    pub fn directory_listing(mut self) -> Self {
+        self.directory_listing = true;
+        self
+    }
+
+    /// Serve a fallback document for missing files, for single page apps.
+    /// Please see the SpaFallback type for when the fallback is used.
+    pub fn spa_fallback(mut self, fallback: SpaFallback) -> Self {
+        self.spa_fallback = Some(fallback);
+        self
+    }
+
+    /// Apply the `_headers` and `_redirects` files at the root of the embed,
+    /// in the same format as Netlify uses.
+    ///
+    /// The paths in these files are relative to where the service is
+    /// mounted. Headers are added to the successful responses for all the
+    /// matching paths, replacing the headers this crate would send. They are
+    /// not added to redirects, `304 Not Modified` or error responses.
+    /// Redirects can use `:splat` and `:placeholder`s, and a `200` or `404`
+    /// status serves the target instead of redirecting to it. Like Netlify,
+    /// a redirect doesn't apply if there is a file at the path, unless the
+    /// status ends with `!`. Proxying to other sites and conditions are not
+    /// supported.
+    ///
+    /// The files are read and parsed here, so you'll get an error for any
+    /// problems with them when your server starts. The files themselves
+    /// will not be served.
+    pub fn netlify_config(mut self) -> Result<Self, NetlifyConfigError> {
+        let read = |path: &str| {
+            self.source
+                .get(path)
+                .map(|file| String::from_utf8_lossy(file.data().as_ref()).into_owned())
+        };
+        let headers = read(HEADERS_FILE);
+        let redirects = read(REDIRECTS_FILE);
+        self.netlify = Some(NetlifyConfig::parse(
+            headers.as_deref(),
+            redirects.as_deref(),
+        )?);
+        Ok(self)
+    }
+
+    /// Send pages from the embed for errors, like a `404.html` for files
+    /// that don't exist. Please see the ErrorPages type for the details.
+    ///
+    /// The pages replace the empty responses this service sends for errors.
+    /// Error pages served by a `404` rewrite in `_redirects`, or the SPA
+    /// fallback, are not replaced.
+    pub fn error_pages(mut self, pages: ErrorPages) -> Self {
+        self.error_pages = Some(pages);
+        self
+    }
+
+    /// Serve the maintenance page for every request while maintenance mode
+    /// is enabled. Please see the MaintenanceMode type for the details.
+    pub fn maintenance_mode(mut self, mode: MaintenanceMode) -> Self {
+        self.maintenance = Some(mode);
+        self
+    }
+
+    /// Set the compression option to use for all files, instead of the one
+    /// in the app's `EmbedConfig`. Please see the Compress type for allowed
+    /// options.
+    pub fn use_compression(mut self, option: Compress) -> Self {
+        self.compress = Some(option);
+        self
+    }
+
+    /// Set rules for compressing some files differently, instead of the ones
+    /// in the app's `EmbedConfig`. Please see the CompressionRules type for
+    /// details.
+    pub fn use_compression_rules(mut self, rules: impl Into<Arc<CompressionRules>>) -> Self {
+        self.compression_rules = Some(rules.into());
+        self
+    }
+
+    /// Set the caching option to use for all files, instead of the one in
+    /// the app's `EmbedConfig`. Please see the CachePolicy type for allowed
+    /// options.
+    pub fn use_cache_policy(mut self, option: CachePolicy) -> Self {
+        self.cache = Some(option);
+        self
+    }
+
+    /// The files of the source, without the ones denied by the rules.
+    fn files(&self) -> AllowedFiles<'_, S> {
+        AllowedFiles {
+            source: &self.source,
+            rules: &self.rules,
+            hide_netlify_files: self.netlify.is_some(),
+        }
+    }
+
+    /// The file inside the embed, for the requested path.
+    fn resolve<'a>(&self, path: &'a str) -> Resolved<'a, S::File> {
+        if path.is_empty() || path.ends_with('/') {
+            let index = match &self.index_file {
+                Some(index_file) => self.resolve_file(Cow::Owned(format!("{path}{index_file}"))),
+                None => Resolved::Missing,
+            };
+            return match index {
+                Resolved::Missing if self.directory_listing => Resolved::Directory,
+                index => index,
+            };
+        }
+        let try_files = match &self.try_files {
+            Some(try_files) => try_files,
+            None => {
+                return match self.resolve_file(Cow::Borrowed(path)) {
+                    Resolved::Missing => self.resolve_directory(path),
+                    resolved => resolved,
+                }
+            }
+        };
+        for candidate in try_files.candidates(path) {
+            match candidate {
+                Candidate::File(candidate) => {
+                    if let Some(file) = self.files().get(&candidate) {
+                        return Resolved::File(Cow::Owned(candidate), file);
+                    }
+                }
+                Candidate::Directory(directory) => {
+                    if let Some(index_file) = &self.index_file {
+                        if self
+                            .files()
+                            .get(&format!("{directory}{index_file}"))
+                            .is_some()
+                        {
+                            return Resolved::AddTrailingSlash;
+                        }
+                    }
+                }
+            }
+        }
+        self.resolve_directory(path)
+    }
+
+    /// A directory requested without the `/` at the end, which has to be
+    /// redirected for the listing to be served.
+    fn resolve_directory<'a>(&self, path: &str) -> Resolved<'a, S::File> {
+        let is_directory = self.directory_listing
+            && self.files().iter().any(|file| {
+                file.strip_prefix(path)
+                    .is_some_and(|rest| rest.starts_with('/'))
+            });
+        if is_directory {
+            Resolved::AddTrailingSlash
+        } else {
+            Resolved::Missing
+        }
+    }
+
+    fn resolve_file<'a>(&self, path: Cow<'a, str>) -> Resolved<'a, S::File> {
+        match self.files().get(&path) {
+            Some(file) => Resolved::File(path, file),
+            None => Resolved::Missing,
+        }
+    }
+
+    fn respond(&self, req: &HttpRequest) -> HttpResponse {
+        if let Some(resp) = self.respond_in_maintenance(req) {
+            return resp;
+        }
+        let path = match normalize_path(req.match_info().query("path")) {
+            Ok(path) => path,
+            Err(error) => {
+                // The page replaces the plain text description of the error
+                return self
+                    .error_page(req, error.status_code())
+                    .unwrap_or_else(|| error.error_response());
+            }
+        };
+        let path = path.as_str();
+        let mut resp = self.with_error_page(req, self.respond_to_path(req, path));
+        if let Some(netlify) = &self.netlify {
+            // A `Cache-Control` for the file shouldn't apply to an error
+            // page, and a `304 Not Modified` keeps the cached headers.
+            if resp.status().is_success() {
+                netlify.apply_headers(&format!("/{path}"), &mut resp);
+            }
+        }
+        resp
+    }
+
+    fn respond_not_allowed(&self, req: &HttpRequest) -> HttpResponse {
+        if let Some(resp) = self.respond_in_maintenance(req) {
+            return resp;
+        }
+        let resp = HttpResponse::MethodNotAllowed()
+            .insert_header((header::ALLOW, "GET, HEAD"))
+            .finish();
+        self.with_error_page(req, resp)
+    }
+
+    /// The maintenance page, if maintenance mode is enabled.
+    fn respond_in_maintenance(&self, req: &HttpRequest) -> Option<HttpResponse> {
+        let mode = self.maintenance.as_ref().filter(|mode| mode.is_enabled())?;
+        let status = StatusCode::SERVICE_UNAVAILABLE;
+        let mut resp = match self.files().get(mode.page()) {
+            Some(page) => send_error_page(req, &page, &self.response_options(req), status),
+            None => HttpResponse::build(status)
+                .insert_header((header::CACHE_CONTROL, CachePolicy::NoStore.header_value()))
+                .finish(),
+        };
+        if let Some(retry_after) = mode.retry_after_header() {
+            resp.headers_mut().insert(header::RETRY_AFTER, retry_after);
+        }
+        Some(resp)
+    }
+
+    /// Replace an empty error response with its error page, if there is one.
+    fn with_error_page(&self, req: &HttpRequest, resp: HttpResponse) -> HttpResponse {
+        let is_empty = matches!(resp.body().size(), BodySize::None | BodySize::Sized(0));
+        // A HEAD response for a page is empty too, but it has a Content-Type
+        if !is_empty || resp.headers().contains_key(header::CONTENT_TYPE) {
+            return resp;
+        }
+        match self.error_page(req, resp.status()) {
+            Some(mut page_resp) => {
+                // Keep headers like `Allow`, which are part of the error
+                for (name, value) in resp.headers() {
+                    if !page_resp.headers().contains_key(name) {
+                        page_resp.headers_mut().append(name.clone(), value.clone());
+                    }
+                }
+                page_resp
+            }
+            None => resp,
+        }
+    }
+
+    fn error_page(&self, req: &HttpRequest, status: StatusCode) -> Option<HttpResponse> {
+        let error_pages = match &self.error_pages {
+            Some(error_pages) => error_pages,
+            None => &embed_config(req)?.error_pages,
+        };
+        let page = self.files().get(error_pages.path_for(status)?)?;
+        Some(send_error_page(
+            req,
+            &page,
+            &self.response_options(req),
+            status,
+        ))
+    }
+
+    /// The settings of this service, with the rest from the app's config.
+    fn response_options(&self, req: &HttpRequest) -> ResponseOptions {
+        ResponseOptions {
+            compress: self.compress,
+            compression_rules: self.compression_rules.clone(),
+            cache: self.cache.clone(),
+            ..Default::default()
+        }
+        .with_config(embed_config(req))
+    }
+
+    fn respond_to_path(&self, req: &HttpRequest, path: &str) -> HttpResponse {
+        // Denied paths look like they don't exist, and don't get the SPA fallback either
+        if !self.files().is_allowed(path) {
+            return EmbedResponse::new(None::<S::File>).respond_to(req);
+        }
+        let resolved = self.resolve(path);
+        if let Some(netlify) = &self.netlify {
+            if let Some(redirect) = netlify.redirect_for(&format!("/{path}")) {
+                if redirect.force || !matches!(resolved, Resolved::File(..)) {
+                    return self.respond_with_redirect(req, redirect);
+                }
+            }
+        }
+        self.respond_resolved(req, path, resolved)
+    }
+
+    fn respond_with_redirect(&self, req: &HttpRequest, redirect: Redirect) -> HttpResponse {
+        let (target, query) = match redirect.target.split_once('?') {
+            Some((target, query)) => (target, Some(query)),
+            None => (redirect.target.as_str(), None),
+        };
+        if !redirect.is_rewrite() {
+            let mut location = encode_url_path(target);
+            if !location.contains("://") {
+                location.insert_str(0, &self.prefix);
+            }
+            match (query, req.query_string()) {
+                (Some(query), _) => location.push_str(&format!("?{query}")),
+                (None, "") => {}
+                (None, query) => location.push_str(&format!("?{query}")),
+            }
+            return HttpResponse::build(redirect.status)
+                .insert_header((header::LOCATION, location))
+                .finish();
+        }
+
+        let path = match normalize_path(target) {
+            Ok(path) => path,
+            Err(error) => return error.error_response(),
+        };
+        if !self.files().is_allowed(&path) {
+            return EmbedResponse::new(None::<S::File>).respond_to(req);
+        }
+        let resolved = self.resolve(&path);
+        if redirect.status == StatusCode::OK {
+            return self.respond_resolved(req, &path, resolved);
+        }
+        // A custom error page, which is sent like the other error pages so
+        // it isn't cached or revalidated as if it was the file.
+        match resolved {
+            Resolved::File(_, page) => {
+                send_error_page(req, &page, &self.response_options(req), redirect.status)
+            }
+            _ => HttpResponse::build(redirect.status).finish(),
+        }
+    }
+
+    fn respond_resolved(
+        &self,
+        req: &HttpRequest,
+        path: &str,
+        resolved: Resolved<'_, S::File>,
+    ) -> HttpResponse {
+        match resolved {
+            Resolved::File(resolved, file) => {
+                let mut resp = EmbedResponse::with_options(Some(file), self.response_options(req))
+                    .respond_to(req);
+                if self.try_files.is_some() && resolved != path {
+                    if let Ok(location) =
+                        HeaderValue::from_str(&content_location(&self.prefix, &resolved))
+                    {
+                        resp.headers_mut()
+                            .insert(header::CONTENT_LOCATION, location);
+                    }
+                }
+                resp
+            }
+            Resolved::AddTrailingSlash => HttpResponse::PermanentRedirect()
+                .insert_header((header::LOCATION, directory_redirect_location(req)))
+                .finish(),
+            Resolved::Directory => {
+                let url_path = format!("{}/{}", self.prefix, encode_url_path(path));
+                match list_directory(&self.files(), &url_path, path) {
+                    Some(listing) => self.respond_with_listing(req, listing),
+                    None => self.respond_missing(req, path),
+                }
+            }
+            Resolved::Missing => self.respond_missing(req, path),
+        }
+    }
+
+    fn respond_missing(&self, req: &HttpRequest, path: &str) -> HttpResponse {
+        match &self.spa_fallback {
+            Some(fallback) if fallback.matches_path(path) => {
+                self.respond_with_fallback(req, fallback)
+            }
+            _ => EmbedResponse::new(None::<S::File>).respond_to(req),
+        }
+    }
+
+    fn respond_with_listing(&self, req: &HttpRequest, listing: Listing) -> HttpResponse {
+        let page = if wants_json(req) {
+            GeneratedPage::new(listing.to_json(), "application/json")
+        } else {
+            GeneratedPage::new(listing.to_html(), "text/html")
+        };
+        // Like the SPA fallback, the listing goes away if an index file is added
+        let mut resp = EmbedResponse::with_options(Some(page), self.response_options(req))
+            .use_cache_policy(CachePolicy::Revalidate)
+            .respond_to(req);
+        resp.headers_mut()
+            .append(header::VARY, HeaderValue::from_static("Accept"));
+        resp
+    }
+
+    fn respond_with_fallback(&self, req: &HttpRequest, fallback: &SpaFallback) -> HttpResponse {
+        let file = if fallback.matches_request(req) {
+            self.files().get(fallback.document())
+        } else {
+            None
+        };
+        // The document will be replaced if a file is added at this path
+        // later, so it always has to be revalidated.
+        let mut resp = EmbedResponse::with_options(file, self.response_options(req))
+            .use_cache_policy(CachePolicy::Revalidate)
+            .respond_to(req);
+        if fallback.varies_on_accept() {
+            resp.headers_mut()
+                .append(header::VARY, HeaderValue::from_static("Accept"));
+        }
+        resp
+    }
+}
+
+/// The files of a source that are allowed by the path rules.
+struct AllowedFiles<'a, S> {
+    source: &'a S,
+    rules: &'a PathRules,
+    /// Whether the `_headers` and `_redirects` files are hidden.
+    hide_netlify_files: bool,
+}
+
+impl<S> AllowedFiles<'_, S> {
+    fn is_allowed(&self, path: &str) -> bool {
+        let is_netlify_file = path == HEADERS_FILE || path == REDIRECTS_FILE;
+        self.rules.is_allowed(path) && !(self.hide_netlify_files && is_netlify_file)
+    }
+}
+
+impl<S: EmbedSource> EmbedSource for AllowedFiles<'_, S> {
+    type File = S::File;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        if self.is_allowed(path) {
+            self.source.get(path)
+        } else {
+            None
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        Box::new(self.source.iter().filter(|path| self.is_allowed(path)))
+    }
+}
+
+enum Resolved<'a, F> {
+    /// The file to serve, and its path inside the embed.
+    File(Cow<'a, str>, F),
+    /// The path is a directory, which has to be requested with a `/` at the end.
+    AddTrailingSlash,
+    /// A directory without an index file, to be listed.
+    Directory,
+    Missing,
+}
+
+impl<S: EmbedSource + 'static> HttpServiceFactory for EmbedService<S>
+where
+    S::File: 'static,
+{
+    fn register(self, config: &mut AppService) {
+        let pattern = format!("{}/{{path:.*}}", self.prefix);
+        if !self.prefix.is_empty() {
+            // The mount path itself is the root directory, like `/static/`
+            let redirect = |req: HttpRequest| async move {
+                HttpResponse::PermanentRedirect()
+                    .insert_header((header::LOCATION, directory_redirect_location(&req)))
+                    .finish()
+            };
+            web::resource(self.prefix.clone())
+                .route(web::get().to(redirect))
+                .route(web::head().to(redirect))
+                .register(config);
+        }
+        let service = Rc::new(self);
+        let not_allowed = {
+            let service = service.clone();
+            move |req: HttpRequest| {
+                let service = service.clone();
+                async move { service.respond_not_allowed(&req) }
+            }
+        };
+        let handler = move |req: HttpRequest| {
+            let service = service.clone();
+            async move { service.respond(&req) }
+        };
+        web::resource(pattern)
+            .route(web::get().to(handler.clone()))
+            .route(web::head().to(handler))
+            .default_service(web::to(not_allowed))
+            .register(config)
+    }
+}
@@ -0,0 +1,222 @@
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+use crate::bounded::BoundedCache;
+
+/// What to send as the `Content-Type` when the file has no known mime type.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MimeFallback {
+    /// Don't send a `Content-Type` header, and let the browser guess.
+    ///
+    /// Browsers will refuse to guess if you also send `X-Content-Type-Options: nosniff`.
+    #[default]
+    Omit,
+    /// Send `application/octet-stream`, which browsers will download rather than display.
+    OctetStream,
+    /// Guess the mime type by looking at the first few bytes of the file.
+    ///
+    /// This recognizes common image and archive formats, PDF, WebAssembly,
+    /// and UTF-8 text. Files that don't look like any of these are sent as
+    /// `application/octet-stream`. Text is always sent as `text/plain`, even
+    /// if it looks like HTML, so a file can't be turned into a page by
+    /// accident.
+    ///
+    /// The result is cached for each file, so the sniffing only happens the
+    /// first time the file is sent.
+    Sniff,
+}
+
+pub(crate) const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Only this much of the file is checked when deciding if it is text.
+const TEXT_SNIFF_LEN: usize = 8192;
+
+/// Magic bytes at the start of the file, and the mime types they indicate.
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"\0asm", "application/wasm"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"PK\x03\x04", "application/zip"),
+    // An empty zip archive has only the end of central directory record
+    (b"PK\x05\x06", "application/zip"),
+];
+
+fn is_webp(data: &[u8]) -> bool {
+    data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP"
+}
+
+fn is_text(data: &[u8]) -> bool {
+    let sample = &data[..data.len().min(TEXT_SNIFF_LEN)];
+    let valid = match std::str::from_utf8(sample) {
+        Ok(text) => text,
+        // We may have cut a multi-byte character in half at the end of the
+        // sample, that's fine as long as everything before it is valid.
+        Err(err) if err.error_len().is_none() => {
+            // This can't fail, we just checked that it's valid up to here.
+            std::str::from_utf8(&sample[..err.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    !valid.is_empty()
+        && !valid
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r' | '\x0c'))
+}
+
+/// Guesses the mime type of the data based on its contents.
+pub(crate) fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+    SIGNATURES
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+        .map(|(_, mime_type)| *mime_type)
+        .or_else(|| is_webp(data).then_some("image/webp"))
+        .or_else(|| is_text(data).then_some("text/plain"))
+}
+
+const MAX_CACHED_MIME_TYPES: usize = 16 * 1024;
+
+// Putting the results into cache could potentially fail. That's okay if it
+// does happen, we'll just sniff the file again next time.
+/// Guesses the mime type of the data based on its contents.
+///
+/// The results are cached based on the ETag of the file, so each file is
+/// only checked once. The results for up to `MAX_CACHED_MIME_TYPES` files
+/// are kept.
+pub(crate) fn sniff_mime_type_cached(etag: &str, data: &[u8]) -> Option<&'static str> {
+    lazy_static! {
+        static ref CACHED_MIME_TYPES: RwLock<BoundedCache<String, Option<&'static str>>> =
+            RwLock::new(BoundedCache::new(MAX_CACHED_MIME_TYPES));
+    }
+
+    if let Some(mime_type) = CACHED_MIME_TYPES
+        .read()
+        .ok()
+        .and_then(|cached| cached.get(etag).copied())
+    {
+        return mime_type;
+    }
+
+    let mime_type = sniff_mime_type(data);
+    if let Ok(mut cached) = CACHED_MIME_TYPES.write() {
+        cached.insert(etag.to_string(), mime_type, 1);
+    }
+    mime_type
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::sniff::{sniff_mime_type, sniff_mime_type_cached};
+
+    #[test]
+    fn sniffs_png() {
+        assert_eq!(
+            sniff_mime_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+    }
+
+    #[test]
+    fn sniffs_jpeg() {
+        let jpeg = include_bytes!("../examples/assets/pexels-ekrulila-13794383.jpg");
+        assert_eq!(sniff_mime_type(jpeg), Some("image/jpeg"));
+    }
+
+    #[test]
+    fn sniffs_gif() {
+        assert_eq!(sniff_mime_type(b"GIF89a\x01\0\x01\0"), Some("image/gif"));
+        assert_eq!(sniff_mime_type(b"GIF87a\x01\0\x01\0"), Some("image/gif"));
+    }
+
+    #[test]
+    fn sniffs_webp() {
+        assert_eq!(
+            sniff_mime_type(b"RIFF\x24\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        // Other RIFF files are not webp
+        assert_ne!(
+            sniff_mime_type(b"RIFF\x24\0\0\0WAVEfmt "),
+            Some("image/webp")
+        );
+    }
+
+    #[test]
+    fn sniffs_pdf() {
+        assert_eq!(sniff_mime_type(b"%PDF-1.7\n"), Some("application/pdf"));
+    }
+
+    #[test]
+    fn sniffs_wasm() {
+        assert_eq!(
+            sniff_mime_type(b"\0asm\x01\0\0\0"),
+            Some("application/wasm")
+        );
+    }
+
+    #[test]
+    fn sniffs_gzip() {
+        assert_eq!(
+            sniff_mime_type(b"\x1f\x8b\x08\0\0\0\0\0"),
+            Some("application/gzip")
+        );
+    }
+
+    #[test]
+    fn sniffs_zip() {
+        assert_eq!(
+            sniff_mime_type(b"PK\x03\x04\x14\0\0\0"),
+            Some("application/zip")
+        );
+        assert_eq!(
+            sniff_mime_type(b"PK\x05\x06\0\0\0\0"),
+            Some("application/zip")
+        );
+    }
+
+    #[test]
+    fn sniffs_text() {
+        assert_eq!(sniff_mime_type(b"hello world\n"), Some("text/plain"));
+        assert_eq!(
+            sniff_mime_type("çok güzel\r\n\tşey".as_bytes()),
+            Some("text/plain")
+        );
+    }
+
+    #[test]
+    fn html_is_sniffed_as_plain_text() {
+        let html = include_bytes!("../examples/assets/index.html");
+        assert_eq!(sniff_mime_type(html), Some("text/plain"));
+    }
+
+    #[test]
+    fn text_cut_in_the_middle_of_a_character_is_text() {
+        let mut text = "a".repeat(8191).into_bytes();
+        text.extend_from_slice("ş".as_bytes());
+        assert_eq!(sniff_mime_type(&text), Some("text/plain"));
+    }
+
+    #[test]
+    fn binary_is_not_sniffed() {
+        assert_eq!(sniff_mime_type(b"\0\x01\x02\x03\xff\xfe"), None);
+        assert_eq!(sniff_mime_type(b"text with a \0 in it"), None);
+        assert_eq!(sniff_mime_type(b""), None);
+    }
+
+    #[test]
+    fn sniffing_is_cached() {
+        assert_eq!(
+            sniff_mime_type_cached("\"sniff-cache\"", b"%PDF-1.7\n"),
+            Some("application/pdf")
+        );
+        // The same ETag means the same file, so the contents are not checked again
+        assert_eq!(
+            sniff_mime_type_cached("\"sniff-cache\"", b"\x89PNG\r\n\x1a\n"),
+            Some("application/pdf")
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+use crate::embed::EmbedRespondable;
+
+/// A collection of files that can be looked up by their paths.
+///
+/// This is implemented by `RustEmbedSource` and `WebEmbedSource` for the
+/// embeds created by `rust-embed` and `rust-embed-for-web`. You can implement
+/// it for your own type if you want to serve files from somewhere else, and
+/// the services of this crate will work with it just the same.
+///
+/// The paths are relative to the root of the source, and don't start with a
+/// `/`. For example `index.html` or `assets/style.css`.
+pub trait EmbedSource {
+    type File: EmbedRespondable;
+
+    /// Get the file at this path, if there is one.
+    fn get(&self, path: &str) -> Option<Self::File>;
+    /// The paths of all the files in the source.
+    ///
+    /// Sources that can't list their files return an empty iterator.
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_>;
+}
+
+impl<S: EmbedSource + ?Sized> EmbedSource for &S {
+    type File = S::File;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        (**self).get(path)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        (**self).iter()
+    }
+}
+
+impl<S: EmbedSource + ?Sized> EmbedSource for Rc<S> {
+    type File = S::File;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        (**self).get(path)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        (**self).iter()
+    }
+}
+
+impl<S: EmbedSource + ?Sized> EmbedSource for Arc<S> {
+    type File = S::File;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        (**self).get(path)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        (**self).iter()
+    }
+}
@@ -0,0 +1,192 @@
+use actix_web::HttpRequest;
+
+use crate::parse::parse_accept_value;
+
+/// Serve a single document for client-side routes of a single page app.
+///
+/// Single page apps handle their routes in the browser, so a request for
+/// `/settings/profile` should get the app's `index.html` instead of a 404.
+/// When the requested file doesn't exist, `EmbedService` will serve the
+/// fallback document instead if all of these are true:
+///
+/// - The path doesn't start with one of the excluded prefixes, like `api/`.
+/// - The last segment of the path doesn't have an extension. A missing
+///   `/logo.png` is still a 404, because the app can't render an image.
+/// - The request accepts `text/html`, which browsers send when navigating to
+///   a page. Requests that don't send an `Accept` header at all also get it.
+///
+/// The fallback document is always sent with `Cache-Control: no-cache`, even
+/// if the service is set to cache files, because the same path will start
+/// serving a real file if one gets added. It also gets `Vary: Accept`, since
+/// the response depends on it.
+///
+/// ```
+/// use actix_web_rust_embed_responder::SpaFallback;
+///
+/// let fallback = SpaFallback::default().exclude_prefix("/api");
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpaFallback {
+    document: String,
+    excluded_prefixes: Vec<String>,
+    skip_with_extension: bool,
+    require_html_accept: bool,
+}
+
+impl Default for SpaFallback {
+    fn default() -> Self {
+        SpaFallback::new("index.html")
+    }
+}
+
+impl SpaFallback {
+    /// Fall back to this document, relative to the root of the embed.
+    pub fn new(document: impl Into<String>) -> Self {
+        SpaFallback {
+            document: document.into().trim_start_matches('/').to_string(),
+            excluded_prefixes: Vec::new(),
+            skip_with_extension: true,
+            require_html_accept: true,
+        }
+    }
+
+    /// Don't fall back for paths under this prefix, like `/api`.
+    ///
+    /// The prefix is relative to where the service is mounted, and matches
+    /// whole path segments: `/api` excludes `/api` and `/api/users`, but not
+    /// `/apiary`.
+    pub fn exclude_prefix(mut self, prefix: impl AsRef<str>) -> Self {
+        self.excluded_prefixes
+            .push(prefix.as_ref().trim_matches('/').to_string());
+        self
+    }
+
+    /// Fall back even if the path looks like a file, with an extension.
+    ///
+    /// This is useful if your app has routes like `/users/jane.doe`.
+    pub fn with_extensions(mut self) -> Self {
+        self.skip_with_extension = false;
+        self
+    }
+
+    /// Fall back for all requests, no matter what they accept.
+    pub fn ignore_accept(mut self) -> Self {
+        self.require_html_accept = false;
+        self
+    }
+
+    /// The path of the fallback document.
+    pub(crate) fn document(&self) -> &str {
+        &self.document
+    }
+
+    /// Whether the response for this path depends on the `Accept` header.
+    pub(crate) fn varies_on_accept(&self) -> bool {
+        self.require_html_accept
+    }
+
+    /// Whether this path is eligible for the fallback, without looking at the request.
+    pub(crate) fn matches_path(&self, path: &str) -> bool {
+        let path = path.trim_start_matches('/');
+        let excluded = self.excluded_prefixes.iter().any(|prefix| {
+            prefix.is_empty()
+                || path
+                    .strip_prefix(prefix.as_str())
+                    .map(|rest| rest.is_empty() || rest.starts_with('/'))
+                    .unwrap_or(false)
+        });
+        !excluded && (!self.skip_with_extension || !has_extension(path))
+    }
+
+    /// Whether the request wants a page, if that is required.
+    pub(crate) fn matches_request(&self, req: &HttpRequest) -> bool {
+        if !self.require_html_accept {
+            return true;
+        }
+        match req.headers().get("Accept") {
+            Some(accept) => parse_accept_value(accept)
+                .map(|media_ranges| {
+                    media_ranges
+                        .iter()
+                        .any(|range| range.eq_ignore_ascii_case("text/html"))
+                })
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+}
+
+fn has_extension(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    match file_name.rsplit_once('.') {
+        // A file like `.well-known` is hidden, and doesn't have an extension
+        Some((stem, extension)) => !stem.is_empty() && !extension.is_empty(),
+        None => false,
+    }
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::spa::SpaFallback;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn routes_match() {
+        let fallback = SpaFallback::default();
+        assert!(fallback.matches_path(""));
+        assert!(fallback.matches_path("settings/profile"));
+        assert!(fallback.matches_path("users/"));
+    }
+
+    #[test]
+    fn paths_with_extensions_do_not_match() {
+        let fallback = SpaFallback::default();
+        assert!(!fallback.matches_path("logo.png"));
+        assert!(!fallback.matches_path("assets/app.js"));
+        assert!(fallback.matches_path("v1.2/changes"));
+        assert!(fallback.matches_path(".well-known"));
+        assert!(SpaFallback::default()
+            .with_extensions()
+            .matches_path("users/jane.doe"));
+    }
+
+    #[test]
+    fn excluded_prefixes_match_whole_segments() {
+        let fallback = SpaFallback::default().exclude_prefix("/api/");
+        assert!(!fallback.matches_path("api"));
+        assert!(!fallback.matches_path("api/users"));
+        assert!(fallback.matches_path("apiary"));
+    }
+
+    #[test]
+    fn browsers_get_the_fallback() {
+        let fallback = SpaFallback::default();
+        let req = TestRequest::default()
+            .insert_header((
+                "Accept",
+                "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+            ))
+            .to_http_request();
+        assert!(fallback.matches_request(&req));
+        let req = TestRequest::default().to_http_request();
+        assert!(fallback.matches_request(&req));
+    }
+
+    #[test]
+    fn other_requests_do_not_get_the_fallback() {
+        let fallback = SpaFallback::default();
+        let req = TestRequest::default()
+            .insert_header(("Accept", "application/json"))
+            .to_http_request();
+        assert!(!fallback.matches_request(&req));
+        let req = TestRequest::default()
+            .insert_header(("Accept", "*/*"))
+            .to_http_request();
+        assert!(!fallback.matches_request(&req));
+        let req = TestRequest::default()
+            .insert_header(("Accept", "text/html;q=0, */*"))
+            .to_http_request();
+        assert!(!fallback.matches_request(&req));
+        assert!(fallback.ignore_accept().matches_request(&req));
+    }
+}
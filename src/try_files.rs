@@ -0,0 +1,131 @@
+use actix_web::HttpRequest;
+
+use crate::helper::encode_url_path;
+
+/// Try a list of files for each request, like the `try_files` of nginx.
+///
+/// Each candidate is a path where `$uri` gets replaced with the requested
+/// path, and the first file that exists is served. The default candidates are
+/// `$uri`, `$uri.html` and `$uri/`, so `/about` will serve `about` if it
+/// exists, then `about.html`.
+///
+/// A candidate ending with a `/` is a directory. If the directory has an
+/// index file, the request is redirected with a `308 Permanent Redirect` to
+/// the same path with a `/` added, so `/docs` redirects to `/docs/` which
+/// then serves `docs/index.html`. This keeps the relative links on the index
+/// page working. If you'd rather serve the index directly, use `$uri/index.html`
+/// as a candidate instead.
+///
+/// Requests for a directory, like `/docs/`, always serve the index file.
+///
+/// ```
+/// use actix_web_rust_embed_responder::TryFiles;
+///
+/// let try_files = TryFiles::new(["$uri", "$uri.html", "$uri.htm", "$uri/"]);
+/// ```
+///
+/// The path of the file that was served is sent in the `Content-Location`
+/// header, if it is different from the requested path.
+#[derive(Clone, Debug)]
+pub struct TryFiles {
+    candidates: Vec<String>,
+}
+
+impl Default for TryFiles {
+    fn default() -> Self {
+        TryFiles::new(["$uri", "$uri.html", "$uri/"])
+    }
+}
+
+/// A path to try for a request.
+pub(crate) enum Candidate {
+    File(String),
+    /// The path of the directory, ending with a `/`.
+    Directory(String),
+}
+
+impl TryFiles {
+    /// Try these candidates, in order.
+    pub fn new(candidates: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        TryFiles {
+            candidates: candidates.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// The paths to try for the requested path.
+    pub(crate) fn candidates<'a>(&'a self, path: &'a str) -> impl Iterator<Item = Candidate> + 'a {
+        self.candidates.iter().map(move |candidate| {
+            let expanded = candidate
+                .replace("$uri", path)
+                .trim_start_matches('/')
+                .to_string();
+            if expanded.ends_with('/') {
+                Candidate::Directory(expanded)
+            } else {
+                Candidate::File(expanded)
+            }
+        })
+    }
+}
+
+/// The location to redirect to, to add a `/` to the requested path.
+pub(crate) fn directory_redirect_location(req: &HttpRequest) -> String {
+    // A path like `//example.com` would turn into a redirect to another site
+    let path = format!("/{}/", req.path().trim_start_matches('/'));
+    match req.query_string() {
+        "" => path,
+        query => format!("{path}?{query}"),
+    }
+}
+
+/// The URL path for a file in an embed mounted at `prefix`.
+pub(crate) fn content_location(prefix: &str, path: &str) -> String {
+    format!("{prefix}/{}", encode_url_path(path))
+}
+
+#[allow(unused_imports)]
+mod test {
+    use crate::try_files::{content_location, directory_redirect_location, Candidate, TryFiles};
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn candidates_are_expanded() {
+        let try_files = TryFiles::default();
+        let candidates: Vec<(bool, String)> = try_files
+            .candidates("about")
+            .map(|candidate| match candidate {
+                Candidate::File(path) => (false, path),
+                Candidate::Directory(path) => (true, path),
+            })
+            .collect();
+        assert_eq!(
+            candidates,
+            vec![
+                (false, "about".to_string()),
+                (false, "about.html".to_string()),
+                (true, "about/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn redirect_keeps_the_query() {
+        let req = TestRequest::get().uri("/docs?page=2").to_http_request();
+        assert_eq!(directory_redirect_location(&req), "/docs/?page=2");
+    }
+
+    #[test]
+    fn redirect_stays_on_the_site() {
+        let req = TestRequest::get().uri("//example.com").to_http_request();
+        assert_eq!(directory_redirect_location(&req), "/example.com/");
+    }
+
+    #[test]
+    fn content_location_is_encoded() {
+        assert_eq!(
+            content_location("/static", "my docs/index.html"),
+            "/static/my%20docs/index.html"
+        );
+        assert_eq!(content_location("", "about.html"), "/about.html");
+    }
+}
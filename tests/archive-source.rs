@@ -0,0 +1,156 @@
+use std::io::{Read, Write};
+
+use actix_web::{test, App};
+use actix_web_rust_embed_responder::{ArchiveSource, EmbedRespondable, EmbedService, EmbedSource};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+fn style_css() -> Vec<u8> {
+    EmbedRE::get("style.css").unwrap().data.into_owned()
+}
+
+fn zip_archive() -> Vec<u8> {
+    let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    writer
+        .add_directory("css/", SimpleFileOptions::default())
+        .unwrap();
+    writer
+        .start_file(
+            "css/style.css",
+            SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+        )
+        .unwrap();
+    writer.write_all(&style_css()).unwrap();
+    writer
+        .start_file(
+            "index.html",
+            SimpleFileOptions::default().compression_method(CompressionMethod::Stored),
+        )
+        .unwrap();
+    writer.write_all(b"<h1>Hello</h1>").unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+fn tar_archive() -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let data = style_css();
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mtime(1_700_000_000);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "./css/style.css", &data[..])
+        .unwrap();
+    builder.into_inner().unwrap()
+}
+
+#[actix_web::test]
+async fn zip_members_are_served() {
+    let source = ArchiveSource::zip(zip_archive()).unwrap();
+    assert_eq!(
+        source.iter().collect::<Vec<_>>(),
+        vec!["css/style.css", "index.html"]
+    );
+    let app = test::init_service(App::new().service(EmbedService::new("/", source))).await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("Last-Modified").is_some());
+    assert_eq!(test::read_body(resp).await, "<h1>Hello</h1>");
+
+    let req = test::TestRequest::get().uri("/css/style.css").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("ETag").unwrap().to_str().unwrap(),
+        EmbedRE::get("style.css").unwrap().etag()
+    );
+    assert_eq!(test::read_body(resp).await, style_css());
+}
+
+#[actix_web::test]
+async fn deflated_zip_members_are_sent_as_gzip() {
+    let source = ArchiveSource::zip(zip_archive()).unwrap();
+    let file = source.get("css/style.css").unwrap();
+    assert!(file.data_gzip().is_some());
+    // Only the gzip data is kept, but the size is of the file itself
+    assert_eq!(file.size(), style_css().len());
+    assert_eq!(file.data(), style_css());
+    assert!(source.get("index.html").unwrap().data_gzip().is_none());
+    let app = test::init_service(App::new().service(EmbedService::new("/", source))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/css/style.css")
+        .append_header(("Accept-Encoding", "gzip"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "gzip");
+    let body = test::read_body(resp).await;
+    let mut decoded = Vec::new();
+    GzDecoder::new(&body[..]).read_to_end(&mut decoded).unwrap();
+    assert_eq!(decoded, style_css());
+}
+
+#[actix_web::test]
+async fn zip_files_are_sent_as_gzip() {
+    // Made with Python's zipfile, noise.bin is random data that deflate made
+    // larger.
+    let source = ArchiveSource::zip(&include_bytes!("assets/archive/site.zip")[..]).unwrap();
+    assert!(source.get("noise.bin").unwrap().data_gzip().is_none());
+    let app = test::init_service(App::new().service(EmbedService::new("/", source))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/css/style.css")
+        .append_header(("Accept-Encoding", "gzip"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "gzip");
+    let body = test::read_body(resp).await;
+    let mut decoded = Vec::new();
+    GzDecoder::new(&body[..]).read_to_end(&mut decoded).unwrap();
+    assert_eq!(decoded, style_css());
+
+    let req = test::TestRequest::get()
+        .uri("/noise.bin")
+        .append_header(("Accept-Encoding", "gzip"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("Content-Encoding").is_none());
+    assert_eq!(test::read_body(resp).await.len(), 4096);
+}
+
+#[actix_web::test]
+async fn encrypted_zip_members_are_errors() {
+    // Made with `zip -P secret`.
+    assert!(ArchiveSource::zip(&include_bytes!("assets/archive/encrypted.zip")[..]).is_err());
+}
+
+#[actix_web::test]
+async fn tar_members_are_served() {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&tar_archive()).unwrap();
+    let tar_gz = encoder.finish().unwrap();
+
+    for source in [
+        ArchiveSource::tar(tar_archive()).unwrap(),
+        ArchiveSource::tar_gz(tar_gz.clone()).unwrap(),
+    ] {
+        let file = source.get("/css/style.css").unwrap();
+        assert_eq!(file.data(), style_css());
+        assert_eq!(file.last_modified_timestamp(), Some(1_700_000_000));
+        assert_eq!(source.iter().collect::<Vec<_>>(), vec!["css/style.css"]);
+    }
+}
+
+#[actix_web::test]
+async fn invalid_archives_are_errors() {
+    assert!(ArchiveSource::zip(&b"not a zip"[..]).is_err());
+    assert!(ArchiveSource::tar_gz(&b"not gzip"[..]).is_err());
+}
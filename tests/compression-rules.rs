@@ -0,0 +1,104 @@
+use actix_web::{route, test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{
+    Compress, CompressionRule, CompressionRules, EmbedConfig, Encoding, IntoResponse,
+};
+
+#[derive(rust_embed_for_web::RustEmbed)]
+#[folder = "examples/assets/"]
+struct Embed;
+
+#[route("/{path:.*}", method = "GET", method = "HEAD")]
+async fn handler(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    let path = path.rsplit('/').next().unwrap_or_default();
+    Embed::get(path).into_response().respond_to(&req)
+}
+
+fn config(default: Compress) -> EmbedConfig {
+    EmbedConfig::new()
+        .use_compression(default)
+        .use_compression_rules(
+            CompressionRules::new()
+                .rule(CompressionRule::path("*.css", Compress::Always))
+                .rule(CompressionRule::path("/downloads/**", Compress::Never))
+                .rule(
+                    CompressionRule::mime("text/html", Compress::Always)
+                        .encodings([Encoding::Gzip]),
+                ),
+        )
+}
+
+async fn content_encoding(default: Compress, uri: &str) -> Option<String> {
+    let app = test::init_service(App::new().app_data(config(default)).service(handler)).await;
+    let req = test::TestRequest::get()
+        .uri(uri)
+        .append_header(("Accept-Encoding", "zstd, br, gzip"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    resp.headers()
+        .get("Content-Encoding")
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[actix_web::test]
+async fn path_rules_override_the_default() {
+    assert!(content_encoding(Compress::Never, "/style.css")
+        .await
+        .is_some());
+    assert_eq!(
+        content_encoding(Compress::Always, "/downloads/style.css").await,
+        None
+    );
+}
+
+#[actix_web::test]
+async fn files_without_a_rule_use_the_default() {
+    assert_eq!(
+        content_encoding(Compress::Never, "/pexels-david-yu-10075042.jpg").await,
+        None
+    );
+    assert!(
+        content_encoding(Compress::Always, "/pexels-david-yu-10075042.jpg")
+            .await
+            .is_some()
+    );
+}
+
+#[actix_web::test]
+async fn rules_limit_the_encodings() {
+    assert_eq!(
+        content_encoding(Compress::Never, "/index.html")
+            .await
+            .as_deref(),
+        Some("gzip")
+    );
+}
+
+#[actix_web::test]
+async fn responses_can_have_their_own_rules() {
+    #[route("/file/{path:.*}", method = "GET")]
+    async fn with_rules(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+        Embed::get(&path)
+            .into_response()
+            .use_compression(Compress::Always)
+            .use_compression_rules(
+                CompressionRules::new().rule(CompressionRule::mime("image/*", Compress::Never)),
+            )
+            .respond_to(&req)
+    }
+
+    let app = test::init_service(App::new().service(with_rules)).await;
+    let req = test::TestRequest::get()
+        .uri("/file/pexels-david-yu-10075042.jpg")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.headers().get("Content-Encoding").is_none());
+
+    let req = test::TestRequest::get()
+        .uri("/file/style.css")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "br");
+}
@@ -0,0 +1,131 @@
+use actix_http::body::MessageBody;
+use actix_web::{
+    dev::{ServiceFactory, ServiceRequest, ServiceResponse},
+    route, web, App,
+};
+use actix_web::{http::StatusCode, test};
+use actix_web_rust_embed_responder::{EmbedResponse, EmbedableFileResponse, IntoResponse};
+
+#[derive(rust_embed_for_web::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedREFW;
+
+#[route("/download/{path:.*}", method = "GET", method = "HEAD")]
+async fn download_handler(path: web::Path<String>) -> EmbedResponse<EmbedableFileResponse> {
+    EmbedREFW::get(&path)
+        .into_response()
+        .as_attachment("Rapor ğüş.html")
+}
+
+#[route("/inline/{path:.*}", method = "GET", method = "HEAD")]
+async fn inline_handler(path: web::Path<String>) -> EmbedResponse<EmbedableFileResponse> {
+    EmbedREFW::get(&path).into_response().inline("page.html")
+}
+
+async fn make_app() -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Response = ServiceResponse<impl MessageBody>,
+        Config = (),
+        InitError = (),
+        Error = actix_web::Error,
+    >,
+> {
+    App::new().service(download_handler).service(inline_handler)
+}
+
+#[actix_web::test]
+async fn attachment_header_is_sent() {
+    let app = test::init_service(make_app().await).await;
+
+    let req = test::TestRequest::get()
+        .uri("/download/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()
+            .get("Content-Disposition")
+            .expect("No disposition header"),
+        "attachment; filename=\"Rapor ___.html\"; filename*=UTF-8''Rapor%20%C4%9F%C3%BC%C5%9F.html"
+    );
+}
+
+#[actix_web::test]
+async fn inline_header_is_sent() {
+    let app = test::init_service(make_app().await).await;
+
+    let req = test::TestRequest::get()
+        .uri("/inline/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers()
+            .get("Content-Disposition")
+            .expect("No disposition header"),
+        "inline; filename=\"page.html\""
+    );
+}
+
+#[actix_web::test]
+async fn header_is_sent_for_head_requests() {
+    let app = test::init_service(make_app().await).await;
+
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::HEAD)
+        .uri("/download/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("Content-Disposition").is_some());
+}
+
+#[actix_web::test]
+async fn header_is_not_sent_for_not_modified() {
+    let app = test::init_service(make_app().await).await;
+
+    let req = test::TestRequest::get()
+        .uri("/download/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let etag = resp.headers().get("ETag").unwrap().clone();
+
+    let req = test::TestRequest::get()
+        .uri("/download/index.html")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+    assert!(resp.headers().get("Content-Disposition").is_none());
+}
+
+#[actix_web::test]
+async fn header_is_not_sent_for_not_found() {
+    let app = test::init_service(make_app().await).await;
+
+    let req = test::TestRequest::get()
+        .uri("/download/does-not-exist.txt")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert!(resp.headers().get("Content-Disposition").is_none());
+}
+
+#[actix_web::test]
+async fn header_is_only_sent_for_ok() {
+    async fn created(path: web::Path<String>) -> EmbedResponse<EmbedableFileResponse> {
+        EmbedREFW::get(&path)
+            .into_response()
+            .as_attachment("page.html")
+            .with_status(StatusCode::CREATED)
+    }
+    let app =
+        test::init_service(App::new().route("/created/{path:.*}", web::get().to(created))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/created/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    assert!(resp.headers().get("Content-Disposition").is_none());
+}
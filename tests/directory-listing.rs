@@ -0,0 +1,171 @@
+use actix_web::{test, App};
+use actix_web_rust_embed_responder::{EmbedService, RustEmbedSource, WebEmbedSource};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/site/"]
+struct Site;
+
+#[derive(rust_embed_for_web::RustEmbed)]
+#[folder = "tests/assets/site/"]
+struct SiteREFW;
+
+fn service() -> EmbedService<RustEmbedSource<Site>> {
+    EmbedService::new("/files", RustEmbedSource::<Site>::new())
+        .without_index_file()
+        .directory_listing()
+}
+
+#[actix_web::test]
+async fn lists_directory_as_html() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get().uri("/files/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("Content-Type").expect("No content type"),
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(
+        resp.headers().get("Vary").expect("No vary header"),
+        "Accept"
+    );
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.contains("<title>Index of /files/</title>"));
+    assert!(body.contains("<a href=\"./blog/\">blog/</a>"));
+    assert!(body.contains("<a href=\"./docs/\">docs/</a>"));
+    assert!(body.contains("<a href=\"./about.html\">about.html</a>"));
+    // Directories are listed before files
+    assert!(body.find("docs/").unwrap() < body.find("about.html").unwrap());
+    assert!(!body.contains("first post.html"));
+}
+
+#[actix_web::test]
+async fn lists_subdirectory_as_json() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get()
+        .uri("/files/blog/")
+        .append_header(("Accept", "application/json"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("Content-Type").expect("No content type"),
+        "application/json; charset=utf-8"
+    );
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    let size = include_bytes!("assets/site/blog/first post.html").len();
+    assert!(body.starts_with(&format!(
+        r#"{{"path":"/files/blog/","entries":[{{"name":"first post.html","type":"file","size":{size},"last_modified":""#
+    )));
+}
+
+#[actix_web::test]
+async fn listing_can_be_revalidated() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get().uri("/files/docs/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers()
+            .get("Cache-Control")
+            .expect("No cache header"),
+        "no-cache"
+    );
+    let etag = resp.headers().get("ETag").expect("No ETag").clone();
+
+    let req = test::TestRequest::get()
+        .uri("/files/docs/")
+        .append_header(("If-None-Match", etag.clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+
+    // A different listing has a different ETag
+    let req = test::TestRequest::get().uri("/files/blog/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_ne!(resp.headers().get("ETag").expect("No ETag"), &etag);
+}
+
+#[actix_web::test]
+async fn index_file_is_preferred() {
+    let app =
+        test::init_service(App::new().service(
+            EmbedService::new("/files", RustEmbedSource::<Site>::new()).directory_listing(),
+        ))
+        .await;
+
+    let req = test::TestRequest::get().uri("/files/docs/").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, include_bytes!("assets/site/docs/index.html")[..]);
+
+    let req = test::TestRequest::get().uri("/files/blog/").to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains("Index of /files/blog/"));
+}
+
+#[actix_web::test]
+async fn missing_directories_are_not_found() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get().uri("/files/nothing/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn directories_without_a_slash_are_redirected() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get()
+        .uri("/files/blog?page=2")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 308);
+    assert_eq!(
+        resp.headers().get("Location").expect("No location header"),
+        "/files/blog/?page=2"
+    );
+
+    // Only directories, not files that start with the same name
+    let req = test::TestRequest::get().uri("/files/about").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn listing_is_off_by_default() {
+    let app =
+        test::init_service(App::new().service(
+            EmbedService::new("/files", RustEmbedSource::<Site>::new()).without_index_file(),
+        ))
+        .await;
+
+    let req = test::TestRequest::get().uri("/files/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn sources_that_cannot_list_only_have_the_root() {
+    let app = test::init_service(
+        App::new().service(
+            EmbedService::new("/files", WebEmbedSource::<SiteREFW>::new())
+                .without_index_file()
+                .directory_listing(),
+        ),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/files/")
+        .append_header(("Accept", "application/json"))
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, r#"{"path":"/files/","entries":[]}"#.as_bytes());
+
+    let req = test::TestRequest::get().uri("/files/docs/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
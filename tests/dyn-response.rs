@@ -0,0 +1,91 @@
+use actix_web::{route, test, web, App};
+use actix_web_rust_embed_responder::{DynEmbedResponse, IntoResponse};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/theme/"]
+struct Theme;
+
+#[derive(rust_embed_for_web::RustEmbed)]
+#[folder = "examples/assets/"]
+struct Base;
+
+#[route("/{path:.*}", method = "GET", method = "HEAD")]
+async fn handler(path: web::Path<String>) -> DynEmbedResponse {
+    match Theme::get(&path) {
+        Some(file) => file.into_response().into_dyn(),
+        None => Base::get(&path).into_response().into_dyn(),
+    }
+}
+
+#[route("/base/{path:.*}", method = "GET", method = "HEAD")]
+async fn base_handler(path: web::Path<String>) -> impl actix_web::Responder {
+    Base::get(&path).into_response()
+}
+
+#[actix_web::test]
+async fn files_from_either_embed_are_served() {
+    let app = test::init_service(App::new().service(base_handler).service(handler)).await;
+
+    let req = test::TestRequest::get().uri("/style.css").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Theme::get("style.css").unwrap().data.as_ref());
+
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let etag = resp.headers().get("ETag").unwrap().clone();
+    let content_type = resp.headers().get("Content-Type").unwrap().clone();
+    let body = test::read_body(resp).await;
+
+    // The same as responding with the file directly
+    let req = test::TestRequest::get()
+        .uri("/base/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("ETag").unwrap(), etag);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), content_type);
+    assert_eq!(test::read_body(resp).await, body);
+
+    let req = test::TestRequest::get().uri("/missing.txt").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn cached_files_are_not_modified() {
+    let app = test::init_service(App::new().service(handler)).await;
+
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    let etag = resp.headers().get("ETag").unwrap().clone();
+
+    let req = test::TestRequest::get()
+        .uri("/index.html")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+}
+
+#[actix_web::test]
+async fn precompressed_data_is_kept() {
+    let app = test::init_service(App::new().service(base_handler).service(handler)).await;
+
+    let req = test::TestRequest::get()
+        .uri("/index.html")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let encoding = resp.headers().get("Content-Encoding").cloned();
+    let body = test::read_body(resp).await;
+
+    let req = test::TestRequest::get()
+        .uri("/base/index.html")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Content-Encoding").cloned(), encoding);
+    assert_eq!(test::read_body(resp).await, body);
+}
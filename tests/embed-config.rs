@@ -0,0 +1,130 @@
+use actix_web::{route, test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{
+    CachePolicy, Compress, EmbedConfig, EmbedService, ErrorPages, IntoResponse, MimeOverrides,
+    WebEmbedSource,
+};
+use rust_embed_for_web::{EmbedableFile, RustEmbed};
+
+#[derive(RustEmbed)]
+#[folder = "tests/assets/errors/"]
+struct Site;
+
+#[route("/files/{path:.*}", method = "GET", method = "HEAD")]
+async fn handler(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    Site::get(&path).into_response().respond_to(&req)
+}
+
+#[route("/uncompressed/{path:.*}", method = "GET", method = "HEAD")]
+async fn uncompressed(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    Site::get(&path)
+        .into_response()
+        .use_compression(Compress::Never)
+        .use_cache_policy(CachePolicy::NoStore)
+        .respond_to(&req)
+}
+
+fn config() -> EmbedConfig {
+    EmbedConfig::new()
+        .use_compression(Compress::Always)
+        .use_cache_policy(CachePolicy::MaxAge(3600))
+        .use_mime_overrides(
+            MimeOverrides::default()
+                .with_extension("html", "text/x-custom")
+                .without_charset(),
+        )
+        .error_pages(ErrorPages::new().not_found("404.html"))
+}
+
+#[actix_web::test]
+async fn responses_use_the_config() {
+    let app = test::init_service(App::new().app_data(config()).service(handler)).await;
+
+    let req = test::TestRequest::get()
+        .uri("/files/index.html")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "br");
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "max-age=3600");
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "text/x-custom");
+}
+
+#[actix_web::test]
+async fn responses_override_the_config() {
+    let app = test::init_service(App::new().app_data(config()).service(uncompressed)).await;
+
+    let req = test::TestRequest::get()
+        .uri("/uncompressed/index.html")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("Content-Encoding").is_none());
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    // Settings that weren't overridden still come from the config
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "text/x-custom");
+}
+
+#[actix_web::test]
+async fn without_config_responses_use_the_defaults() {
+    let app = test::init_service(App::new().service(handler)).await;
+
+    let req = test::TestRequest::get()
+        .uri("/files/index.html")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-cache");
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+}
+
+#[actix_web::test]
+async fn services_use_the_config() {
+    let app = test::init_service(
+        App::new()
+            .app_data(config())
+            .service(EmbedService::new("/", WebEmbedSource::<Site>::new())),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "max-age=3600");
+
+    let req = test::TestRequest::get().uri("/missing.txt").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Site::get("404.html").unwrap().data());
+}
+
+#[actix_web::test]
+async fn services_override_the_config() {
+    let app = test::init_service(
+        App::new().app_data(config()).service(
+            EmbedService::new("/", WebEmbedSource::<Site>::new())
+                .use_cache_policy(CachePolicy::Immutable)
+                .error_pages(ErrorPages::new()),
+        ),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("Cache-Control").unwrap(),
+        "max-age=31536000, immutable"
+    );
+
+    let req = test::TestRequest::get().uri("/missing.txt").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let body = test::read_body(resp).await;
+    assert!(body.is_empty());
+}
@@ -0,0 +1,138 @@
+use actix_web::{test, web, App};
+use actix_web_rust_embed_responder::{
+    EmbedPath, EmbedPathConfig, EmbedResponse, EmbedService, IntoResponse, RustEmbedSource,
+};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/site/"]
+struct Site;
+
+async fn handler(path: EmbedPath) -> EmbedResponse<rust_embed::EmbeddedFile> {
+    Site::get(&path).into_response()
+}
+
+async fn echo(path: EmbedPath) -> String {
+    path.into_inner()
+}
+
+#[actix_web::test]
+async fn files_are_served() {
+    let app = test::init_service(App::new().route("/site/{path:.*}", web::get().to(handler))).await;
+
+    for (uri, expected) in [
+        (
+            "/site/about.html",
+            &include_bytes!("assets/site/about.html")[..],
+        ),
+        ("/site/", &include_bytes!("assets/site/index.html")[..]),
+        (
+            "/site/docs/",
+            &include_bytes!("assets/site/docs/index.html")[..],
+        ),
+        (
+            "/site/docs//./index.html",
+            &include_bytes!("assets/site/docs/index.html")[..],
+        ),
+        (
+            "/site/blog/../about.html",
+            &include_bytes!("assets/site/about.html")[..],
+        ),
+        (
+            "/site/blog/first%20post.html",
+            &include_bytes!("assets/site/blog/first post.html")[..],
+        ),
+    ] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200, "{uri}");
+        assert_eq!(test::read_body(resp).await, expected, "{uri}");
+    }
+}
+
+#[actix_web::test]
+async fn hostile_paths_are_rejected() {
+    let app = test::init_service(
+        App::new()
+            .route("/site/{path:.*}", web::get().to(handler))
+            .service(EmbedService::new(
+                "/service",
+                RustEmbedSource::<Site>::new(),
+            )),
+    )
+    .await;
+
+    for path in [
+        "%2e%2e/Cargo.toml",
+        "..%2fCargo.toml",
+        "%2e%2e%2f%2e%2e%2fetc%2fpasswd",
+        "docs/%2e%2e/%2e%2e/Cargo.toml",
+        "about.html%00.png",
+        "..%5cCargo.toml",
+        "%5c%5cserver%5cshare",
+        "C:%2fWindows%2fwin.ini",
+    ] {
+        for prefix in ["/site", "/service"] {
+            let uri = format!("{prefix}/{path}");
+            let req = test::TestRequest::get().uri(&uri).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), 400, "{uri}");
+        }
+    }
+}
+
+#[actix_web::test]
+async fn overlong_encodings_are_not_served() {
+    let app = test::init_service(App::new().route("/site/{path:.*}", web::get().to(handler))).await;
+
+    // actix-web replaces the invalid UTF-8 before the extractor sees it, so
+    // this is a file that doesn't exist rather than a bad request.
+    let req = test::TestRequest::get()
+        .uri("/site/%c0%ae%c0%ae/Cargo.toml")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_client_error());
+}
+
+#[actix_web::test]
+async fn prefix_is_stripped() {
+    let app = test::init_service(
+        App::new()
+            .app_data(EmbedPathConfig::default().prefix("/static/v1/"))
+            .default_service(web::get().to(echo)),
+    )
+    .await;
+
+    for (uri, expected) in [
+        ("/static/v1/app.js", "app.js"),
+        ("/static/v1", "index.html"),
+        ("/static//v1/./docs/", "docs/index.html"),
+    ] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        assert_eq!(body, expected.as_bytes(), "{uri}");
+    }
+
+    for uri in [
+        "/static/v2/app.js",
+        "/static/v1x/app.js",
+        "/static/v1/../../x",
+    ] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404, "{uri}");
+    }
+}
+
+#[actix_web::test]
+async fn index_file_can_be_disabled() {
+    let app = test::init_service(
+        App::new()
+            .app_data(EmbedPathConfig::default().without_index_file())
+            .route("/{path:.*}", web::get().to(echo)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/docs/").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "docs/".as_bytes());
+}
@@ -0,0 +1,186 @@
+use actix_web::{http::Method, test, App};
+use actix_web_rust_embed_responder::{
+    CachePolicy, Compress, EmbedService, RustEmbedSource, WebEmbedSource,
+};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+#[derive(rust_embed_for_web::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedREFW;
+
+#[actix_web::test]
+async fn serves_files_at_the_root() {
+    let app = test::init_service(
+        App::new().service(EmbedService::new("/", RustEmbedSource::<EmbedRE>::new())),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/style.css").to_request();
+    let resp = test::call_and_read_body(&app, req).await;
+    assert_eq!(resp, include_bytes!("../examples/assets/style.css")[..]);
+}
+
+#[actix_web::test]
+async fn serves_index_file() {
+    let app = test::init_service(
+        App::new().service(EmbedService::new("", WebEmbedSource::<EmbedREFW>::new())),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_and_read_body(&app, req).await;
+    assert!(resp.starts_with(b"<!DOCTYPE html>"));
+}
+
+#[actix_web::test]
+async fn custom_index_file() {
+    let app = test::init_service(App::new().service(
+        EmbedService::new("/", RustEmbedSource::<EmbedRE>::new()).index_file("style.css"),
+    ))
+    .await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_and_read_body(&app, req).await;
+    assert_eq!(resp, include_bytes!("../examples/assets/style.css")[..]);
+}
+
+#[actix_web::test]
+async fn without_index_file() {
+    let app =
+        test::init_service(App::new().service(
+            EmbedService::new("/", RustEmbedSource::<EmbedRE>::new()).without_index_file(),
+        ))
+        .await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn mounts_at_prefix() {
+    let app = test::init_service(
+        App::new()
+            .service(EmbedService::new("/re/", RustEmbedSource::<EmbedRE>::new()))
+            .service(EmbedService::new(
+                "/refw",
+                WebEmbedSource::<EmbedREFW>::new(),
+            )),
+    )
+    .await;
+
+    for uri in ["/re/", "/re/index.html", "/refw/", "/refw/index.html"] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_and_read_body(&app, req).await;
+        assert!(resp.starts_with(b"<!DOCTYPE html>"), "{uri} did not match");
+    }
+
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn bare_prefix_is_redirected() {
+    let app = test::init_service(App::new().service(EmbedService::new(
+        "/static",
+        RustEmbedSource::<EmbedRE>::new(),
+    )))
+    .await;
+
+    let req = test::TestRequest::get().uri("/static?v=1").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 308);
+    assert_eq!(resp.headers().get("Location").unwrap(), "/static/?v=1");
+
+    let req = test::TestRequest::get().uri("/staticfile").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn missing_file_is_not_found() {
+    let app = test::init_service(
+        App::new().service(EmbedService::new("/", RustEmbedSource::<EmbedRE>::new())),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/does-not-exist.txt")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn responds_to_head() {
+    let app = test::init_service(
+        App::new().service(EmbedService::new("/", RustEmbedSource::<EmbedRE>::new())),
+    )
+    .await;
+
+    let req = test::TestRequest::default()
+        .method(Method::HEAD)
+        .uri("/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("ETag").is_some());
+}
+
+#[actix_web::test]
+async fn other_methods_are_not_allowed() {
+    let app = test::init_service(
+        App::new().service(EmbedService::new("/", RustEmbedSource::<EmbedRE>::new())),
+    )
+    .await;
+
+    let req = test::TestRequest::post().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 405);
+}
+
+#[actix_web::test]
+async fn uses_compression_and_cache_policy() {
+    let app = test::init_service(
+        App::new().service(
+            EmbedService::new("/", RustEmbedSource::<EmbedRE>::new())
+                .use_compression(Compress::Always)
+                .use_cache_policy(CachePolicy::Immutable),
+        ),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/index.html")
+        .append_header(("Accept-Encoding", "gzip"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "gzip");
+    assert_eq!(
+        resp.headers().get("Cache-Control").unwrap(),
+        "max-age=31536000, immutable"
+    );
+}
+
+#[actix_web::test]
+async fn revalidation_works() {
+    let app = test::init_service(
+        App::new().service(EmbedService::new("/", WebEmbedSource::<EmbedREFW>::new())),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/style.css").to_request();
+    let resp = test::call_service(&app, req).await;
+    let etag = resp.headers().get("ETag").unwrap().clone();
+
+    let req = test::TestRequest::get()
+        .uri("/style.css")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+}
@@ -0,0 +1,70 @@
+use std::borrow::Cow;
+
+use actix_web::{test, App};
+use actix_web_rust_embed_responder::{EmbedService, EmbedSource, RustEmbedSource, WebEmbedSource};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+#[derive(rust_embed_for_web::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedREFW;
+
+/// A source that serves the files of another source under different names.
+struct Renamed<S> {
+    inner: S,
+    names: Vec<(&'static str, &'static str)>,
+}
+
+impl<S: EmbedSource> EmbedSource for Renamed<S> {
+    type File = S::File;
+
+    fn get(&self, path: &str) -> Option<Self::File> {
+        let (_, original) = self.names.iter().find(|(name, _)| *name == path)?;
+        self.inner.get(original)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        Box::new(self.names.iter().map(|(name, _)| Cow::Borrowed(*name)))
+    }
+}
+
+#[actix_web::test]
+async fn rust_embed_source_lists_files() {
+    let source = RustEmbedSource::<EmbedRE>::new();
+    let mut paths: Vec<String> = source.iter().map(|path| path.into_owned()).collect();
+    paths.sort();
+    assert!(paths.contains(&"index.html".to_string()));
+    assert!(paths.contains(&"style.css".to_string()));
+    for path in paths {
+        assert!(source.get(&path).is_some());
+    }
+}
+
+#[actix_web::test]
+async fn rust_embed_for_web_source_gets_files() {
+    let source = WebEmbedSource::<EmbedREFW>::new();
+    assert!(source.get("style.css").is_some());
+    assert!(source.get("does-not-exist.txt").is_none());
+    assert_eq!(source.iter().count(), 0);
+}
+
+#[actix_web::test]
+async fn custom_source_is_served() {
+    let source = Renamed {
+        inner: RustEmbedSource::<EmbedRE>::new(),
+        names: vec![("main.css", "style.css")],
+    };
+    assert_eq!(source.iter().collect::<Vec<_>>(), vec!["main.css"]);
+
+    let app = test::init_service(App::new().service(EmbedService::new("/", source))).await;
+
+    let req = test::TestRequest::get().uri("/main.css").to_request();
+    let resp = test::call_and_read_body(&app, req).await;
+    assert_eq!(resp, include_bytes!("../examples/assets/style.css")[..]);
+
+    let req = test::TestRequest::get().uri("/style.css").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
@@ -0,0 +1,166 @@
+use actix_web::{http::StatusCode, route, test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{
+    Compress, EmbedService, ErrorPages, IntoResponse, WebEmbedSource,
+};
+use rust_embed_for_web::{EmbedableFile, RustEmbed};
+
+#[derive(RustEmbed)]
+#[folder = "tests/assets/errors/"]
+struct Site;
+
+fn service() -> EmbedService<WebEmbedSource<Site>> {
+    EmbedService::new("/", WebEmbedSource::<Site>::new()).error_pages(
+        ErrorPages::new()
+            .not_found("404.html")
+            .page(StatusCode::METHOD_NOT_ALLOWED, "405.html")
+            .page(StatusCode::BAD_REQUEST, "400.html"),
+    )
+}
+
+#[route("/files/{path:.*}", method = "GET", method = "HEAD")]
+async fn handler(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    Site::get(&path)
+        .into_response()
+        .not_found_with(Site::get("404.html"))
+        .respond_to(&req)
+}
+
+#[actix_web::test]
+async fn missing_files_get_the_not_found_page() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get().uri("/missing.txt").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    assert!(resp.headers().get("ETag").is_none());
+    assert!(resp.headers().get("Last-Modified").is_none());
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Site::get("404.html").unwrap().data());
+}
+
+#[actix_web::test]
+async fn head_requests_get_the_headers_of_the_page() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::HEAD)
+        .uri("/missing.txt")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    let body = test::read_body(resp).await;
+    assert!(body.is_empty());
+}
+
+#[actix_web::test]
+async fn error_pages_are_compressed() {
+    let app =
+        test::init_service(App::new().service(service().use_compression(Compress::Always))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/missing.txt")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "br");
+}
+
+#[actix_web::test]
+async fn other_methods_get_method_not_allowed() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::post().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 405);
+    assert_eq!(resp.headers().get("Allow").unwrap(), "GET, HEAD");
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Site::get("405.html").unwrap().data());
+}
+
+#[actix_web::test]
+async fn responses_to_other_methods_are_not_allowed() {
+    let app = test::init_service(App::new().route(
+        "/files/{path:.*}",
+        web::to(|req: HttpRequest, path: web::Path<String>| async move {
+            Site::get(&path).into_response().respond_to(&req)
+        }),
+    ))
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/files/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 405);
+    assert_eq!(resp.headers().get("Allow").unwrap(), "GET, HEAD");
+}
+
+#[actix_web::test]
+async fn invalid_paths_get_the_bad_request_page() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get().uri("/a%5Cb.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Site::get("400.html").unwrap().data());
+}
+
+#[actix_web::test]
+async fn without_error_pages_errors_are_empty() {
+    let app = test::init_service(
+        App::new().service(EmbedService::new("/", WebEmbedSource::<Site>::new())),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/missing.txt").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let body = test::read_body(resp).await;
+    assert!(body.is_empty());
+
+    let req = test::TestRequest::post().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 405);
+    assert_eq!(resp.headers().get("Allow").unwrap(), "GET, HEAD");
+}
+
+#[actix_web::test]
+async fn responses_can_have_a_not_found_page() {
+    let app = test::init_service(App::new().service(handler)).await;
+
+    let req = test::TestRequest::get()
+        .uri("/files/missing.txt")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Site::get("404.html").unwrap().data());
+
+    let req = test::TestRequest::get()
+        .uri("/files/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Site::get("index.html").unwrap().data());
+}
@@ -0,0 +1,148 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use actix_web::{test, App, Responder};
+use actix_web_rust_embed_responder::{
+    Compress, EmbedRespondable, EmbedService, EmbedSource, FsSource, IntoResponse, MemoryFile,
+};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+/// A new directory for each test, with a copy of `style.css` in it.
+fn temp_dir(name: &str) -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "fs-source-{}-{}-{}",
+        name,
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("public/css")).unwrap();
+    fs::write(
+        dir.join("public/css/style.css"),
+        EmbedRE::get("style.css").unwrap().data,
+    )
+    .unwrap();
+    dir
+}
+
+#[actix_web::test]
+async fn files_have_the_same_etag_as_embedded_ones() {
+    let dir = temp_dir("etag");
+    let source = FsSource::new(dir.join("public")).unwrap();
+    let app = test::init_service(App::new().service(EmbedService::new("/", source))).await;
+
+    let req = test::TestRequest::get().uri("/css/style.css").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let etag = resp.headers().get("ETag").unwrap().clone();
+    assert!(resp.headers().get("Last-Modified").is_some());
+    let embedded = EmbedRE::get("style.css").unwrap();
+    assert_eq!(etag.to_str().unwrap(), embedded.etag());
+
+    let req = test::TestRequest::get()
+        .uri("/css/style.css")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[actix_web::test]
+async fn files_are_compressed() {
+    let dir = temp_dir("compress");
+    let source = FsSource::new(dir.join("public")).unwrap();
+    let app = test::init_service(
+        App::new().service(EmbedService::new("/", source).use_compression(Compress::Always)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/css/style.css")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "br");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[actix_web::test]
+async fn changed_files_get_a_new_etag() {
+    let dir = temp_dir("changed");
+    let source = FsSource::new(dir.join("public")).unwrap();
+    fs::write(dir.join("public/data.txt"), "first").unwrap();
+    let first = source.get("data.txt").unwrap().etag();
+    fs::write(dir.join("public/data.txt"), "second version").unwrap();
+    let second = source.get("data.txt").unwrap();
+    assert_ne!(first.as_ref(), second.etag().as_ref());
+    assert_eq!(second.data().as_ref(), b"second version");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[actix_web::test]
+async fn files_changed_after_get_keep_a_matching_etag() {
+    let dir = temp_dir("race");
+    let source = FsSource::new(dir.join("public")).unwrap();
+    fs::write(dir.join("public/data.txt"), "first").unwrap();
+    let file = source.get("data.txt").unwrap();
+    assert_eq!(file.size(), 5);
+    // Written between looking the file up and reading it
+    fs::write(dir.join("public/data.txt"), "second version").unwrap();
+    let expected = MemoryFile::new(file.data()).etag();
+    assert_eq!(file.etag().as_ref(), expected.as_ref());
+
+    // The hash isn't cached for the old size and time, so the next lookup
+    // hashes the file again.
+    let file = source.get("data.txt").unwrap();
+    assert_eq!(file.etag().as_ref(), expected.as_ref());
+    assert_eq!(file.size(), 14);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[actix_web::test]
+async fn files_deleted_after_get_are_not_found() {
+    let dir = temp_dir("deleted");
+    let source = FsSource::new(dir.join("public")).unwrap();
+    // The hash is cached, so the next lookup doesn't read the file.
+    assert!(source.get("css/style.css").unwrap().etag_header().is_some());
+    let file = source.get("css/style.css").unwrap();
+    fs::remove_file(dir.join("public/css/style.css")).unwrap();
+
+    let req = test::TestRequest::get()
+        .uri("/css/style.css")
+        .to_http_request();
+    let resp = file.into_response().respond_to(&req);
+    assert_eq!(resp.status(), 404);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[actix_web::test]
+async fn paths_outside_the_directory_are_rejected() {
+    let dir = temp_dir("escape");
+    fs::write(dir.join("secret.txt"), "secret").unwrap();
+    let source = FsSource::new(dir.join("public")).unwrap();
+    assert!(source.get("../secret.txt").is_none());
+    assert!(source.get("/css/../../secret.txt").is_none());
+    assert!(source.get("css").is_none());
+    assert!(source.get("css/style.css").is_some());
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(dir.join("secret.txt"), dir.join("public/link.txt")).unwrap();
+        std::os::unix::fs::symlink(dir.join("public/css"), dir.join("public/styles")).unwrap();
+        assert!(source.get("link.txt").is_none());
+        assert!(source.get("styles/style.css").is_some());
+        assert_eq!(
+            source.iter().collect::<Vec<_>>(),
+            vec!["css/style.css", "styles/style.css"]
+        );
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
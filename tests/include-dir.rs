@@ -0,0 +1,65 @@
+use actix_web::{route, test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{
+    EmbedRespondable, EmbedService, EmbedSource, IncludeDirSource, IntoResponse,
+};
+use include_dir::{include_dir, Dir};
+
+static ASSETS: Dir = include_dir!("$CARGO_MANIFEST_DIR/examples/assets");
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+#[route("/{path:.*}", method = "GET", method = "HEAD")]
+async fn handler(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    ASSETS
+        .get_file(path.as_str())
+        .into_response()
+        .respond_to(&req)
+}
+
+#[actix_web::test]
+async fn files_are_served_with_a_mime_type() {
+    let app = test::init_service(App::new().service(handler)).await;
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    let etag = resp.headers().get("ETag").unwrap().clone();
+    assert_eq!(
+        etag.to_str().unwrap(),
+        EmbedRE::get("index.html").unwrap().etag()
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/index.html")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+}
+
+#[actix_web::test]
+async fn missing_files_are_not_found() {
+    let app = test::init_service(App::new().service(handler)).await;
+    let req = test::TestRequest::get().uri("/missing.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn source_can_be_served() {
+    let source = IncludeDirSource::new(&ASSETS);
+    assert!(source.iter().any(|path| path == "style.css"));
+    let app = test::init_service(App::new().service(EmbedService::new("/", source))).await;
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        test::read_body(resp).await,
+        EmbedRE::get("index.html").unwrap().data.as_ref()
+    );
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use actix_web::{http::StatusCode, route, test, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{EmbedService, IntoResponse, MaintenanceMode, WebEmbedSource};
+use rust_embed_for_web::{EmbedableFile, RustEmbed};
+
+#[derive(RustEmbed)]
+#[folder = "tests/assets/errors/"]
+struct Site;
+
+#[route("/maintenance", method = "GET", method = "HEAD")]
+async fn maintenance(req: HttpRequest) -> HttpResponse {
+    Site::get("maintenance.html")
+        .into_response()
+        .with_status(StatusCode::SERVICE_UNAVAILABLE)
+        .respond_to(&req)
+}
+
+#[route("/accepted", method = "GET", method = "HEAD")]
+async fn accepted(req: HttpRequest) -> HttpResponse {
+    Site::get("index.html")
+        .into_response()
+        .with_status(StatusCode::ACCEPTED)
+        .respond_to(&req)
+}
+
+#[actix_web::test]
+async fn error_statuses_are_not_cached() {
+    let app = test::init_service(App::new().service(maintenance)).await;
+
+    let etag = Site::get("maintenance.html").unwrap().etag();
+    let req = test::TestRequest::get()
+        .uri("/maintenance")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    assert!(resp.headers().get("ETag").is_none());
+    assert!(resp.headers().get("Last-Modified").is_none());
+    let body = test::read_body(resp).await;
+    assert_eq!(body, Site::get("maintenance.html").unwrap().data());
+}
+
+#[actix_web::test]
+async fn success_statuses_skip_conditions() {
+    let app = test::init_service(App::new().service(accepted)).await;
+
+    let etag = Site::get("index.html").unwrap().etag();
+    let req = test::TestRequest::get()
+        .uri("/accepted")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-cache");
+    assert!(resp.headers().get("ETag").is_some());
+}
+
+#[actix_web::test]
+async fn maintenance_mode_can_be_toggled() {
+    let mode = MaintenanceMode::new("maintenance.html").retry_after(Duration::from_secs(600));
+    let app = test::init_service(App::new().service(
+        EmbedService::new("/", WebEmbedSource::<Site>::new()).maintenance_mode(mode.clone()),
+    ))
+    .await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("Retry-After").is_none());
+
+    mode.enable();
+    for uri in ["/", "/missing.txt"] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 503);
+        assert_eq!(resp.headers().get("Retry-After").unwrap(), "600");
+        assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+        let body = test::read_body(resp).await;
+        assert_eq!(body, Site::get("maintenance.html").unwrap().data());
+    }
+    let req = test::TestRequest::post().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+
+    mode.disable();
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[actix_web::test]
+async fn maintenance_without_a_page_is_empty() {
+    let mode = MaintenanceMode::new("missing.html");
+    mode.enable();
+    let app = test::init_service(
+        App::new()
+            .service(EmbedService::new("/", WebEmbedSource::<Site>::new()).maintenance_mode(mode)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+    assert!(resp.headers().get("Retry-After").is_none());
+    let body = test::read_body(resp).await;
+    assert!(body.is_empty());
+}
@@ -0,0 +1,173 @@
+use actix_web::{route, test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{EmbedService, IntoResponse, MemoryFile, MemoryFiles};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+#[route("/generated/{path:.*}", method = "GET", method = "HEAD")]
+async fn handler(
+    req: HttpRequest,
+    files: web::Data<MemoryFiles>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    files.get(&path).into_response().respond_to(&req)
+}
+
+#[route("/embedded/{path:.*}", method = "GET", method = "HEAD")]
+async fn embedded(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    EmbedRE::get(&path).into_response().respond_to(&req)
+}
+
+fn files() -> MemoryFiles {
+    let files = MemoryFiles::new();
+    files.insert(
+        "config.json",
+        MemoryFile::new(r#"{"api": "/api/v2"}"#)
+            .with_mime_type("application/json")
+            .with_last_modified(1_700_000_000),
+    );
+    files.insert(
+        "style.css",
+        MemoryFile::new(EmbedRE::get("style.css").unwrap().data.into_owned())
+            .with_mime_type("text/css")
+            .precompress(),
+    );
+    files
+}
+
+#[actix_web::test]
+async fn memory_files_are_served() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(files()))
+            .service(handler),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/generated/config.json")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "application/json; charset=utf-8"
+    );
+    assert_eq!(
+        resp.headers().get("Last-Modified").unwrap(),
+        "Tue, 14 Nov 2023 22:13:20 +0000"
+    );
+    let etag = resp.headers().get("ETag").unwrap().clone();
+    let body = test::read_body(resp).await;
+    assert_eq!(body, r#"{"api": "/api/v2"}"#);
+
+    let req = test::TestRequest::get()
+        .uri("/generated/config.json")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+
+    let req = test::TestRequest::get()
+        .uri("/generated/missing.json")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn etag_matches_the_embedded_file() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(files()))
+            .service(handler)
+            .service(embedded),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/generated/style.css")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let etag = resp.headers().get("ETag").unwrap().clone();
+
+    let req = test::TestRequest::get()
+        .uri("/embedded/style.css")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("ETag").unwrap(), etag);
+}
+
+#[actix_web::test]
+async fn precompressed_files_are_compressed() {
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(files()))
+            .service(handler),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/generated/style.css")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "br");
+
+    // Without precompression, the default is not to compress
+    let req = test::TestRequest::get()
+        .uri("/generated/config.json")
+        .append_header(("Accept-Encoding", "br"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.headers().get("Content-Encoding").is_none());
+}
+
+#[actix_web::test]
+async fn set_mime_type_wins_over_the_extension() {
+    let files = MemoryFiles::new();
+    files.insert(
+        "config.js",
+        MemoryFile::new(r#"{"api": "/api/v2"}"#).with_mime_type("application/json"),
+    );
+    let app = test::init_service(App::new().service(EmbedService::new("/", files))).await;
+
+    let req = test::TestRequest::get().uri("/config.js").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "application/json; charset=utf-8"
+    );
+}
+
+#[actix_web::test]
+async fn files_can_be_updated_while_serving() {
+    let files = files();
+    let app =
+        test::init_service(App::new().service(EmbedService::new("/generated", files.clone())))
+            .await;
+
+    let req = test::TestRequest::get()
+        .uri("/generated/config.json")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let etag = resp.headers().get("ETag").unwrap().clone();
+
+    files.insert("config.json", MemoryFile::new(r#"{"api": "/api/v3"}"#));
+    let req = test::TestRequest::get()
+        .uri("/generated/config.json")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body = test::read_body(resp).await;
+    assert_eq!(body, r#"{"api": "/api/v3"}"#);
+
+    files.remove("config.json");
+    let req = test::TestRequest::get()
+        .uri("/generated/config.json")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
@@ -0,0 +1,153 @@
+use actix_web::{test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{Compress, EmbedRespondable, IntoResponse, MimeFallback};
+
+/// A file with no mime type, like a `rust-embed` file without the `mime-guess` feature.
+struct UnknownFile {
+    data: &'static [u8],
+    etag: &'static str,
+}
+
+impl EmbedRespondable for UnknownFile {
+    type Data = &'static [u8];
+    type DataGzip = &'static [u8];
+    type DataBr = &'static [u8];
+    type DataZstd = &'static [u8];
+    type MimeType = &'static str;
+    type ETag = &'static str;
+    type LastModified = &'static str;
+
+    fn data(&self) -> Self::Data {
+        self.data
+    }
+
+    fn data_gzip(&self) -> Option<Self::DataGzip> {
+        None
+    }
+
+    fn data_br(&self) -> Option<Self::DataBr> {
+        None
+    }
+
+    fn data_zstd(&self) -> Option<Self::DataZstd> {
+        None
+    }
+
+    fn last_modified_timestamp(&self) -> Option<i64> {
+        None
+    }
+
+    fn last_modified(&self) -> Option<Self::LastModified> {
+        None
+    }
+
+    fn etag(&self) -> Self::ETag {
+        self.etag
+    }
+
+    fn mime_type(&self) -> Option<Self::MimeType> {
+        None
+    }
+}
+
+fn get_file(name: &str) -> Option<UnknownFile> {
+    match name {
+        "image" => Some(UnknownFile {
+            data: include_bytes!("../examples/assets/pexels-ekrulila-13794383.jpg"),
+            etag: "\"fallback-image\"",
+        }),
+        "text" => Some(UnknownFile {
+            data: include_bytes!("../examples/assets/style.css"),
+            etag: "\"fallback-text\"",
+        }),
+        "binary" => Some(UnknownFile {
+            data: b"\0\x01\x02\x03\x04",
+            etag: "\"fallback-binary\"",
+        }),
+        _ => None,
+    }
+}
+
+async fn handler(req: HttpRequest, path: web::Path<(String, String)>) -> HttpResponse {
+    let (fallback, name) = path.into_inner();
+    let fallback = match fallback.as_str() {
+        "omit" => MimeFallback::Omit,
+        "octet" => MimeFallback::OctetStream,
+        "sniff" => MimeFallback::Sniff,
+        _ => panic!("Unknown fallback!"),
+    };
+    get_file(&name)
+        .into_response()
+        .use_mime_fallback(fallback)
+        .use_compression(Compress::IfWellKnown)
+        .respond_to(&req)
+}
+
+async fn content_type(fallback: &str, name: &str) -> Option<String> {
+    let app =
+        test::init_service(App::new().route("/{fallback}/{name}", web::get().to(handler))).await;
+    let req = test::TestRequest::get()
+        .uri(&format!("/{fallback}/{name}"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    resp.headers()
+        .get("Content-Type")
+        .map(|v| v.to_str().unwrap().to_string())
+}
+
+#[actix_web::test]
+async fn omit_sends_no_content_type() {
+    assert_eq!(content_type("omit", "image").await, None);
+    assert_eq!(content_type("omit", "text").await, None);
+}
+
+#[actix_web::test]
+async fn octet_stream_is_sent_for_everything() {
+    assert_eq!(
+        content_type("octet", "image").await.as_deref(),
+        Some("application/octet-stream")
+    );
+    assert_eq!(
+        content_type("octet", "text").await.as_deref(),
+        Some("application/octet-stream")
+    );
+}
+
+#[actix_web::test]
+async fn sniffing_detects_the_type() {
+    assert_eq!(
+        content_type("sniff", "image").await.as_deref(),
+        Some("image/jpeg")
+    );
+    assert_eq!(
+        content_type("sniff", "text").await.as_deref(),
+        Some("text/plain; charset=utf-8")
+    );
+    assert_eq!(
+        content_type("sniff", "binary").await.as_deref(),
+        Some("application/octet-stream")
+    );
+}
+
+#[actix_web::test]
+async fn sniffed_text_is_compressed_if_well_known() {
+    let app =
+        test::init_service(App::new().route("/{fallback}/{name}", web::get().to(handler))).await;
+    let req = test::TestRequest::get()
+        .uri("/sniff/text")
+        .append_header(("Accept-Encoding", "gzip"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers()
+            .get("Content-Encoding")
+            .expect("No encoding header"),
+        "gzip"
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/sniff/image")
+        .append_header(("Accept-Encoding", "gzip"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.headers().get("Content-Encoding").is_none());
+}
@@ -0,0 +1,156 @@
+use actix_http::body::MessageBody;
+use actix_web::test;
+use actix_web::{
+    dev::{ServiceFactory, ServiceRequest, ServiceResponse},
+    route, web, App,
+};
+use actix_web_rust_embed_responder::{
+    Compress, EmbedResponse, IntoResponse, MimeFallback, NamedEmbeddedFile, RustEmbedExt,
+};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/rules/"]
+struct EmbedUnknown;
+
+#[route("/re/{path:.*}", method = "GET", method = "HEAD")]
+async fn re_handler(path: web::Path<String>) -> EmbedResponse<rust_embed::EmbeddedFile> {
+    EmbedRE::get(&path)
+        .into_response()
+        .use_compression(Compress::IfWellKnown)
+}
+
+#[route("/re-named/{path:.*}", method = "GET", method = "HEAD")]
+async fn re_named_handler(path: web::Path<String>) -> EmbedResponse<NamedEmbeddedFile> {
+    EmbedRE::get_named(&path)
+        .into_response()
+        .use_compression(Compress::IfWellKnown)
+}
+
+#[route("/sniff/{path:.*}", method = "GET", method = "HEAD")]
+async fn sniff_handler(path: web::Path<String>) -> EmbedResponse<rust_embed::EmbeddedFile> {
+    EmbedUnknown::get(&path)
+        .into_response()
+        .use_mime_fallback(MimeFallback::Sniff)
+}
+
+async fn make_app() -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Response = ServiceResponse<impl MessageBody>,
+        Config = (),
+        InitError = (),
+        Error = actix_web::Error,
+    >,
+> {
+    App::new()
+        .service(re_handler)
+        .service(re_named_handler)
+        .service(sniff_handler)
+}
+
+#[actix_web::test]
+async fn metadata_mime_type_is_sent() {
+    let app = test::init_service(make_app().await).await;
+
+    let req = test::TestRequest::get().uri("/re/style.css").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers()
+            .get("Content-Type")
+            .expect("No content type header"),
+        "text/css; charset=utf-8"
+    );
+}
+
+#[actix_web::test]
+async fn extension_mime_type_is_sent() {
+    let app = test::init_service(make_app().await).await;
+
+    let req = test::TestRequest::get()
+        .uri("/re-named/index.html")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers()
+            .get("Content-Type")
+            .expect("No content type header"),
+        "text/html; charset=utf-8"
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/re-named/pexels-ekrulila-13794383.jpg")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers()
+            .get("Content-Type")
+            .expect("No content type header"),
+        "image/jpeg"
+    );
+}
+
+#[actix_web::test]
+async fn unknown_mime_type_is_sniffed() {
+    let app = test::init_service(make_app().await).await;
+
+    // rust-embed has no mime type for this file, so the fallback is used.
+    let req = test::TestRequest::get().uri("/sniff/.env").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers()
+            .get("Content-Type")
+            .expect("No content type header"),
+        "text/plain; charset=utf-8"
+    );
+}
+
+#[actix_web::test]
+async fn if_well_known_compresses_html() {
+    let app = test::init_service(make_app().await).await;
+
+    for uri in ["/re/index.html", "/re-named/index.html"] {
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .append_header(("Accept-Encoding", "gzip"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(
+            resp.headers()
+                .get("Content-Encoding")
+                .expect("No encoding header"),
+            "gzip"
+        );
+    }
+}
+
+#[actix_web::test]
+async fn if_well_known_does_not_compress_images() {
+    let app = test::init_service(make_app().await).await;
+
+    for uri in [
+        "/re/pexels-ekrulila-13794383.jpg",
+        "/re-named/pexels-ekrulila-13794383.jpg",
+    ] {
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .append_header(("Accept-Encoding", "gzip"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.headers().get("Content-Encoding").is_none());
+    }
+}
+
+#[actix_web::test]
+async fn missing_named_file_is_not_found() {
+    let app = test::init_service(make_app().await).await;
+
+    let req = test::TestRequest::get()
+        .uri("/re-named/does-not-exist.txt")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
@@ -0,0 +1,88 @@
+use actix_web::{test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{IntoResponse, MimeOverrides};
+
+#[derive(rust_embed_for_web::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedREFW;
+
+async fn handler(req: HttpRequest, path: web::Path<(String, String)>) -> HttpResponse {
+    let (overrides, path) = path.into_inner();
+    let overrides = match overrides.as_str() {
+        "default" => MimeOverrides::default(),
+        "none" => MimeOverrides::none(),
+        "custom" => MimeOverrides::default()
+            .with_extension("css", "text/x-custom")
+            .with_charset("iso-8859-1"),
+        "nocharset" => MimeOverrides::default().without_charset(),
+        _ => panic!("Unknown overrides!"),
+    };
+    EmbedREFW::get(&path)
+        .into_response()
+        .use_mime_overrides(overrides)
+        .respond_to(&req)
+}
+
+async fn content_type(overrides: &str, path: &str) -> Option<String> {
+    let app =
+        test::init_service(App::new().route("/{overrides}/{path:.*}", web::get().to(handler)))
+            .await;
+    let req = test::TestRequest::get()
+        .uri(&format!("/{overrides}/{path}"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    resp.headers()
+        .get("Content-Type")
+        .map(|v| v.to_str().unwrap().to_string())
+}
+
+#[actix_web::test]
+async fn text_gets_a_charset_by_default() {
+    let app = test::init_service(
+        App::new().route(
+            "/{path:.*}",
+            web::get()
+                .to(|path: web::Path<String>| async move { EmbedREFW::get(&path).into_response() }),
+        ),
+    )
+    .await;
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+}
+
+#[actix_web::test]
+async fn binary_files_get_no_charset() {
+    assert_eq!(
+        content_type("default", "pexels-ekrulila-13794383.jpg")
+            .await
+            .as_deref(),
+        Some("image/jpeg")
+    );
+}
+
+#[actix_web::test]
+async fn overrides_replace_the_guessed_type() {
+    assert_eq!(
+        content_type("custom", "style.css").await.as_deref(),
+        Some("text/x-custom; charset=iso-8859-1")
+    );
+    assert_eq!(
+        content_type("custom", "index.html").await.as_deref(),
+        Some("text/html; charset=iso-8859-1")
+    );
+}
+
+#[actix_web::test]
+async fn charset_can_be_disabled() {
+    assert_eq!(
+        content_type("nocharset", "style.css").await.as_deref(),
+        Some("text/css")
+    );
+    assert_eq!(
+        content_type("none", "style.css").await.as_deref(),
+        Some("text/css")
+    );
+}
@@ -0,0 +1,173 @@
+use actix_web::{test, App};
+use actix_web_rust_embed_responder::{CachePolicy, EmbedService, RustEmbedSource};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/netlify/"]
+struct Site;
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/netlify-invalid/"]
+struct Invalid;
+
+fn service(prefix: &str) -> EmbedService<RustEmbedSource<Site>> {
+    EmbedService::new(prefix, RustEmbedSource::<Site>::new())
+        .netlify_config()
+        .expect("Invalid Netlify config")
+}
+
+#[actix_web::test]
+async fn headers_are_added() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get().uri("/assets/app.js").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("X-Frame-Options").unwrap(), "DENY");
+    assert_eq!(
+        resp.headers().get("Access-Control-Allow-Origin").unwrap(),
+        "*"
+    );
+    assert_eq!(
+        resp.headers().get("Cache-Control").unwrap(),
+        "public, max-age=31536000, immutable"
+    );
+
+    let req = test::TestRequest::get().uri("/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("X-Frame-Options").unwrap(), "DENY");
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-cache");
+}
+
+#[actix_web::test]
+async fn headers_are_only_added_to_successful_responses() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get().uri("/assets/app.js").to_request();
+    let resp = test::call_service(&app, req).await;
+    let etag = resp.headers().get("ETag").unwrap().clone();
+    let req = test::TestRequest::get()
+        .uri("/assets/app.js")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+    assert!(resp.headers().get("X-Frame-Options").is_none());
+    assert!(resp.headers().get("Cache-Control").is_none());
+
+    // The 404 page must not be cached like the asset would be
+    let req = test::TestRequest::get()
+        .uri("/assets/missing.js")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert!(resp.headers().get("X-Frame-Options").is_none());
+    assert_ne!(
+        resp.headers().get("Cache-Control").unwrap(),
+        "public, max-age=31536000, immutable"
+    );
+}
+
+#[actix_web::test]
+async fn redirects_fill_in_the_splat() {
+    let app = test::init_service(App::new().service(service("/site"))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/site/old/a/b?x=1")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 301);
+    assert_eq!(resp.headers().get("Location").unwrap(), "/site/new/a/b?x=1");
+
+    let req = test::TestRequest::get()
+        .uri("/site/docs/intro")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 308);
+    assert_eq!(
+        resp.headers().get("Location").unwrap(),
+        "https://docs.example.com/intro"
+    );
+}
+
+#[actix_web::test]
+async fn files_shadow_redirects_unless_forced() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get().uri("/shadowed").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, include_bytes!("assets/netlify/shadowed")[..]);
+
+    let req = test::TestRequest::get().uri("/forced").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 302);
+    assert_eq!(resp.headers().get("Location").unwrap(), "/index.html");
+}
+
+#[actix_web::test]
+async fn rewrites_serve_the_target() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get().uri("/app/settings").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body = test::read_body(resp).await;
+    assert_eq!(body, include_bytes!("assets/netlify/app.html")[..]);
+}
+
+#[actix_web::test]
+async fn not_found_page_is_served() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get().uri("/missing").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert!(resp.headers().get("ETag").is_none());
+    let body = test::read_body(resp).await;
+    assert_eq!(body, include_bytes!("assets/netlify/404.html")[..]);
+}
+
+#[actix_web::test]
+async fn not_found_page_is_not_cached_or_revalidated() {
+    let app = test::init_service(
+        App::new().service(service("/").use_cache_policy(CachePolicy::Immutable)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/missing")
+        .append_header(("If-None-Match", "*"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    assert!(resp.headers().get("Last-Modified").is_none());
+    let body = test::read_body(resp).await;
+    assert_eq!(body, include_bytes!("assets/netlify/404.html")[..]);
+}
+
+#[actix_web::test]
+async fn config_files_are_not_served() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    for uri in ["/_headers", "/_redirects"] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404, "{uri}");
+        let body = test::read_body(resp).await;
+        assert!(body.is_empty(), "{uri}");
+    }
+}
+
+#[actix_web::test]
+async fn errors_are_reported() {
+    let error = EmbedService::new("/", RustEmbedSource::<Invalid>::new())
+        .netlify_config()
+        .err()
+        .expect("Invalid config was accepted");
+    assert_eq!(error.file, "_redirects");
+    assert_eq!(error.line, 2);
+    assert_eq!(
+        error.to_string(),
+        "_redirects line 2: expected a path and where to send it"
+    );
+}
@@ -0,0 +1,128 @@
+use actix_web::{test, App};
+use actix_web_rust_embed_responder::{
+    EmbedService, EmbedSource, Overlay, RustEmbedSource, WebEmbedSource,
+};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/theme/"]
+struct Theme;
+
+#[derive(rust_embed_for_web::RustEmbed)]
+#[folder = "examples/assets/"]
+struct Base;
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/site/"]
+struct Site;
+
+fn overlay() -> Overlay<RustEmbedSource<Theme>, WebEmbedSource<Base>> {
+    Overlay::new(
+        RustEmbedSource::<Theme>::new(),
+        WebEmbedSource::<Base>::new(),
+    )
+}
+
+#[actix_web::test]
+async fn first_source_takes_precedence() {
+    let app = test::init_service(App::new().service(EmbedService::new("/", overlay()))).await;
+
+    let req = test::TestRequest::get().uri("/style.css").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, include_bytes!("assets/theme/style.css")[..]);
+
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, include_bytes!("../examples/assets/index.html")[..]);
+}
+
+#[actix_web::test]
+async fn files_keep_their_own_etags() {
+    let theme = test::init_service(
+        App::new().service(EmbedService::new("/", RustEmbedSource::<Theme>::new())),
+    )
+    .await;
+    let base = test::init_service(
+        App::new().service(EmbedService::new("/", WebEmbedSource::<Base>::new())),
+    )
+    .await;
+    let app = test::init_service(App::new().service(EmbedService::new("/", overlay()))).await;
+
+    for (uri, expected) in [
+        (
+            "/style.css",
+            test::call_service(
+                &theme,
+                test::TestRequest::get().uri("/style.css").to_request(),
+            )
+            .await,
+        ),
+        (
+            "/index.html",
+            test::call_service(
+                &base,
+                test::TestRequest::get().uri("/index.html").to_request(),
+            )
+            .await,
+        ),
+    ] {
+        let resp = test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        assert_eq!(
+            resp.headers().get("ETag"),
+            expected.headers().get("ETag"),
+            "{uri}"
+        );
+        assert!(resp.headers().get("ETag").is_some());
+    }
+}
+
+#[actix_web::test]
+async fn compression_matches_the_source() {
+    let base = test::init_service(
+        App::new().service(EmbedService::new("/", WebEmbedSource::<Base>::new())),
+    )
+    .await;
+    let app = test::init_service(App::new().service(EmbedService::new("/", overlay()))).await;
+
+    let request = || {
+        test::TestRequest::get()
+            .uri("/index.html")
+            .append_header(("Accept-Encoding", "br"))
+            .to_request()
+    };
+    let expected = test::call_service(&base, request()).await;
+    let resp = test::call_service(&app, request()).await;
+    assert_eq!(
+        resp.headers().get("Content-Encoding"),
+        expected.headers().get("Content-Encoding")
+    );
+    assert_eq!(test::read_body(resp).await, test::read_body(expected).await);
+}
+
+#[actix_web::test]
+async fn missing_everywhere_is_not_found() {
+    let app = test::init_service(App::new().service(EmbedService::new("/", overlay()))).await;
+
+    let req = test::TestRequest::get().uri("/missing.txt").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn more_sources_can_be_stacked() {
+    let source = overlay().then(RustEmbedSource::<Site>::new());
+    assert!(source.get("about.html").is_some());
+
+    let mut paths: Vec<String> = source.iter().map(|path| path.into_owned()).collect();
+    paths.sort();
+    // `index.html` is in both the base and the site, but is listed once
+    assert_eq!(
+        paths,
+        vec![
+            "about.html",
+            "blog/first post.html",
+            "docs/index.html",
+            "index.html",
+            "style.css",
+        ]
+    );
+}
@@ -0,0 +1,70 @@
+use actix_web::{test, App};
+use actix_web_rust_embed_responder::{EmbedService, PathRules, RustEmbedSource, SpaFallback};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/rules/"]
+struct Assets;
+
+fn service() -> EmbedService<RustEmbedSource<Assets>> {
+    EmbedService::new("/", RustEmbedSource::<Assets>::new())
+        .path_rules(PathRules::production().deny("/internal/").deny("README.md"))
+}
+
+async fn status(service: EmbedService<RustEmbedSource<Assets>>, uri: &str) -> u16 {
+    let app = test::init_service(App::new().service(service)).await;
+    let req = test::TestRequest::get().uri(uri).to_request();
+    test::call_service(&app, req).await.status().as_u16()
+}
+
+#[actix_web::test]
+async fn everything_is_served_without_rules() {
+    let service = || EmbedService::new("/", RustEmbedSource::<Assets>::new());
+    assert_eq!(status(service(), "/.env").await, 200);
+    assert_eq!(status(service(), "/app.js.map").await, 200);
+}
+
+#[actix_web::test]
+async fn denied_paths_are_not_found() {
+    for uri in [
+        "/.env",
+        "/app.js.map",
+        "/internal/notes.txt",
+        "/README.md",
+        "/internal/",
+        "/%2eenv",
+    ] {
+        assert_eq!(status(service(), uri).await, 404, "{uri}");
+    }
+}
+
+#[actix_web::test]
+async fn allowed_paths_are_served() {
+    for uri in ["/", "/app.js", "/.well-known/security.txt"] {
+        assert_eq!(status(service(), uri).await, 200, "{uri}");
+    }
+}
+
+#[actix_web::test]
+async fn denied_paths_do_not_get_the_spa_fallback() {
+    let service = || service().spa_fallback(SpaFallback::default().ignore_accept());
+    assert_eq!(status(service(), "/.env").await, 404);
+    assert_eq!(status(service(), "/internal/notes").await, 404);
+    assert_eq!(status(service(), "/settings").await, 200);
+}
+
+#[actix_web::test]
+async fn denied_files_are_not_listed() {
+    let app =
+        test::init_service(App::new().service(service().without_index_file().directory_listing()))
+            .await;
+    let req = test::TestRequest::get()
+        .uri("/")
+        .append_header(("Accept", "application/json"))
+        .to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains("\"app.js\""));
+    assert!(body.contains("\".well-known\""));
+    for hidden in ["app.js.map", ".env", "internal", "README.md"] {
+        assert!(!body.contains(&format!("\"{hidden}\"")), "{hidden}");
+    }
+}
@@ -0,0 +1,125 @@
+use actix_web::{test, App};
+use actix_web_rust_embed_responder::{CachePolicy, EmbedService, RustEmbedSource, SpaFallback};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+const BROWSER_ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+
+fn service() -> EmbedService<RustEmbedSource<EmbedRE>> {
+    EmbedService::new("/", RustEmbedSource::<EmbedRE>::new())
+        .use_cache_policy(CachePolicy::Immutable)
+        .spa_fallback(SpaFallback::default().exclude_prefix("/api"))
+}
+
+#[actix_web::test]
+async fn client_routes_get_the_index() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get()
+        .uri("/settings/profile")
+        .append_header(("Accept", BROWSER_ACCEPT))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()
+            .get("Cache-Control")
+            .expect("No cache header"),
+        "no-cache"
+    );
+    assert_eq!(
+        resp.headers().get("Vary").expect("No vary header"),
+        "Accept"
+    );
+    assert!(resp.headers().get("ETag").is_some());
+    let body = test::read_body(resp).await;
+    assert!(body.starts_with(b"<!DOCTYPE html>"));
+}
+
+#[actix_web::test]
+async fn fallback_document_can_be_revalidated() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get().uri("/about").to_request();
+    let resp = test::call_service(&app, req).await;
+    let etag = resp.headers().get("ETag").expect("No ETag").clone();
+
+    let req = test::TestRequest::get()
+        .uri("/somewhere/else")
+        .append_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+}
+
+#[actix_web::test]
+async fn existing_files_are_served_as_usual() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get()
+        .uri("/style.css")
+        .append_header(("Accept", BROWSER_ACCEPT))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()
+            .get("Cache-Control")
+            .expect("No cache header"),
+        "max-age=31536000, immutable"
+    );
+    assert!(resp.headers().get("Vary").is_none());
+}
+
+#[actix_web::test]
+async fn excluded_and_file_paths_are_not_found() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    for uri in ["/api/users", "/api", "/missing.png", "/assets/app.js"] {
+        let req = test::TestRequest::get()
+            .uri(uri)
+            .append_header(("Accept", BROWSER_ACCEPT))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 404, "{uri}");
+    }
+}
+
+#[actix_web::test]
+async fn requests_not_accepting_html_are_not_found() {
+    let app = test::init_service(App::new().service(service())).await;
+
+    let req = test::TestRequest::get()
+        .uri("/settings/profile")
+        .append_header(("Accept", "application/json"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(
+        resp.headers().get("Vary").expect("No vary header"),
+        "Accept"
+    );
+}
+
+#[actix_web::test]
+async fn custom_document() {
+    let app = test::init_service(
+        App::new().service(
+            EmbedService::new("/", RustEmbedSource::<EmbedRE>::new())
+                .spa_fallback(SpaFallback::new("/style.css").ignore_accept()),
+        ),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/settings")
+        .append_header(("Accept", "application/json"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("Vary").is_none());
+    let body = test::read_body(resp).await;
+    assert_eq!(body, include_bytes!("../examples/assets/style.css")[..]);
+}
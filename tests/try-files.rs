@@ -0,0 +1,113 @@
+use actix_web::{test, App};
+use actix_web_rust_embed_responder::{EmbedService, RustEmbedSource, TryFiles};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "tests/assets/site/"]
+struct Site;
+
+fn service(prefix: &str) -> EmbedService<RustEmbedSource<Site>> {
+    EmbedService::new(prefix, RustEmbedSource::<Site>::new()).try_files(TryFiles::default())
+}
+
+#[actix_web::test]
+async fn clean_urls_serve_html_files() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get().uri("/about").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()
+            .get("Content-Location")
+            .expect("No content location header"),
+        "/about.html"
+    );
+    let body = test::read_body(resp).await;
+    assert_eq!(body, include_bytes!("assets/site/about.html")[..]);
+}
+
+#[actix_web::test]
+async fn exact_files_have_no_content_location() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get().uri("/about.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("Content-Location").is_none());
+}
+
+#[actix_web::test]
+async fn directories_redirect_with_trailing_slash() {
+    let app = test::init_service(App::new().service(service("/site"))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/site/docs?page=2")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 308);
+    assert_eq!(
+        resp.headers().get("Location").expect("No location header"),
+        "/site/docs/?page=2"
+    );
+
+    let req = test::TestRequest::get().uri("/site/docs/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()
+            .get("Content-Location")
+            .expect("No content location header"),
+        "/site/docs/index.html"
+    );
+}
+
+#[actix_web::test]
+async fn content_location_is_encoded() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/blog/first%20post")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()
+            .get("Content-Location")
+            .expect("No content location header"),
+        "/blog/first%20post.html"
+    );
+}
+
+#[actix_web::test]
+async fn custom_candidates() {
+    let app = test::init_service(
+        App::new().service(
+            EmbedService::new("/", RustEmbedSource::<Site>::new())
+                .try_files(TryFiles::new(["$uri", "$uri/index.html"])),
+        ),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/docs").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()
+            .get("Content-Location")
+            .expect("No content location header"),
+        "/docs/index.html"
+    );
+
+    let req = test::TestRequest::get().uri("/about").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn missing_paths_are_not_found() {
+    let app = test::init_service(App::new().service(service("/"))).await;
+
+    let req = test::TestRequest::get().uri("/blog").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
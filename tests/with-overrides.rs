@@ -0,0 +1,100 @@
+use std::io::Read;
+
+use actix_web::{route, test, web, App, HttpRequest, HttpResponse, Responder};
+use actix_web_rust_embed_responder::{
+    Compress, IntoResponse, MemoryFile, MimeOverrides, WithOverrides,
+};
+use flate2::read::GzDecoder;
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "examples/assets/"]
+struct EmbedRE;
+
+const BUILD_TIME: i64 = 1_700_000_000;
+
+#[route("/{path:.*}", method = "GET", method = "HEAD")]
+async fn handler(req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    EmbedRE::get(&path)
+        .map(|file| {
+            WithOverrides::new(file)
+                .with_etag(format!("build-{BUILD_TIME}"))
+                .with_weak_etag()
+                .with_last_modified(BUILD_TIME)
+                .with_mime_type("text/plain")
+        })
+        .into_response()
+        .use_compression(Compress::Always)
+        .respond_to(&req)
+}
+
+#[actix_web::test]
+async fn overridden_headers_are_sent() {
+    let app = test::init_service(App::new().service(handler)).await;
+    let req = test::TestRequest::get().uri("/index.html").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("ETag").unwrap(),
+        "W/\"build-1700000000\""
+    );
+    assert_eq!(
+        resp.headers().get("Last-Modified").unwrap(),
+        "Tue, 14 Nov 2023 22:13:20 +0000"
+    );
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "text/plain; charset=utf-8"
+    );
+}
+
+#[actix_web::test]
+async fn weak_etags_match_if_none_match() {
+    let app = test::init_service(App::new().service(handler)).await;
+    for etag in ["W/\"build-1700000000\"", "\"build-1700000000\""] {
+        let req = test::TestRequest::get()
+            .uri("/index.html")
+            .append_header(("If-None-Match", etag))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 304);
+    }
+}
+
+#[actix_web::test]
+async fn files_with_the_same_etag_are_compressed_separately() {
+    let app = test::init_service(App::new().service(handler)).await;
+    for path in ["index.html", "style.css"] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/{path}"))
+            .append_header(("Accept-Encoding", "gzip"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get("Content-Encoding").unwrap(), "gzip");
+        let body = test::read_body(resp).await;
+        let mut decoded = Vec::new();
+        GzDecoder::new(&body[..]).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, EmbedRE::get(path).unwrap().data.as_ref());
+    }
+}
+
+#[actix_web::test]
+async fn mime_type_wins_over_extension_overrides() {
+    let app = test::init_service(App::new().route(
+        "/config.js",
+        web::get().to(|req: HttpRequest| async move {
+            let file = MemoryFile::new("{}").with_path("config.js");
+            Some(WithOverrides::new(file).with_mime_type("application/json"))
+                .into_response()
+                .use_mime_overrides(MimeOverrides::default())
+                .respond_to(&req)
+        }),
+    ))
+    .await;
+    let req = test::TestRequest::get().uri("/config.js").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("Content-Type").unwrap(),
+        "application/json; charset=utf-8"
+    );
+}